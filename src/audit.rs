@@ -0,0 +1,237 @@
+use serenity::all::{
+    ChannelId, Context, CreateEmbed, CreateMessage, GuildId, Mentionable, Timestamp, UserId,
+};
+use sqlx::{Database, Pool};
+use tracing::warn;
+
+use crate::TempVoiceGuildManager;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    AutoCreate,
+    AutoDelete,
+    Create,
+    Claim,
+    Join,
+    Persist,
+    Name,
+    Limit,
+    Privacy,
+    Trust,
+    Untrust,
+    Invite,
+    Kick,
+    Region,
+    Block,
+    Unblock,
+    Delete,
+    Bitrate,
+    Password,
+    Reset,
+    Transfer,
+    Speaker,
+    Hibernate,
+    Restore,
+    Expire,
+    Ban,
+    Unban,
+}
+
+impl AuditAction {
+    pub fn from_command(name: &str) -> Option<Self> {
+        let action = match name {
+            "create" => Self::Create,
+            "claim" => Self::Claim,
+            "join" => Self::Join,
+            "persist" => Self::Persist,
+            "name" => Self::Name,
+            "limit" => Self::Limit,
+            "privacy" => Self::Privacy,
+            "trust" => Self::Trust,
+            "untrust" => Self::Untrust,
+            "invite" => Self::Invite,
+            "kick" => Self::Kick,
+            "region" => Self::Region,
+            "block" => Self::Block,
+            "unblock" => Self::Unblock,
+            "delete" => Self::Delete,
+            "bitrate" => Self::Bitrate,
+            "password" => Self::Password,
+            "reset" => Self::Reset,
+            "transfer" => Self::Transfer,
+            "speaker" => Self::Speaker,
+            "restore" => Self::Restore,
+            _ => return None,
+        };
+
+        Some(action)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AutoCreate => "auto_create",
+            Self::AutoDelete => "auto_delete",
+            Self::Create => "create",
+            Self::Claim => "claim",
+            Self::Join => "join",
+            Self::Persist => "persist",
+            Self::Name => "name",
+            Self::Limit => "limit",
+            Self::Privacy => "privacy",
+            Self::Trust => "trust",
+            Self::Untrust => "untrust",
+            Self::Invite => "invite",
+            Self::Kick => "kick",
+            Self::Region => "region",
+            Self::Block => "block",
+            Self::Unblock => "unblock",
+            Self::Delete => "delete",
+            Self::Bitrate => "bitrate",
+            Self::Password => "password",
+            Self::Reset => "reset",
+            Self::Transfer => "transfer",
+            Self::Speaker => "speaker",
+            Self::Hibernate => "hibernate",
+            Self::Restore => "restore",
+            Self::Expire => "expire",
+            Self::Ban => "ban",
+            Self::Unban => "unban",
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            Self::AutoCreate => "Channel Created",
+            Self::AutoDelete => "Channel Deleted",
+            Self::Create => "Channel Created",
+            Self::Claim => "Channel Claimed",
+            Self::Join => "Joined With Password",
+            Self::Persist => "Persistence Toggled",
+            Self::Name => "Channel Renamed",
+            Self::Limit => "User Limit Changed",
+            Self::Privacy => "Privacy Changed",
+            Self::Trust => "User Trusted",
+            Self::Untrust => "User Untrusted",
+            Self::Invite => "User Invited",
+            Self::Kick => "User Kicked",
+            Self::Region => "Region Changed",
+            Self::Block => "User Blocked",
+            Self::Unblock => "User Unblocked",
+            Self::Delete => "Channel Deleted",
+            Self::Bitrate => "Bitrate Changed",
+            Self::Password => "Password Set",
+            Self::Reset => "Channel Reset",
+            Self::Transfer => "Ownership Transferred",
+            Self::Speaker => "Speaker Promoted",
+            Self::Hibernate => "Channel Hibernated",
+            Self::Restore => "Channel Restored",
+            Self::Expire => "Channel Expired",
+            Self::Ban => "User Banned",
+            Self::Unban => "User Unbanned",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AuditEvent {
+    pub guild_id: GuildId,
+    pub channel_id: Option<ChannelId>,
+    pub user_id: UserId,
+    pub target_id: Option<UserId>,
+    pub action: AuditAction,
+    pub moderator_override: bool,
+    pub timestamp: Timestamp,
+}
+
+impl AuditEvent {
+    pub fn new(
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        user_id: UserId,
+        action: AuditAction,
+    ) -> Self {
+        Self {
+            guild_id,
+            channel_id: Some(channel_id),
+            user_id,
+            target_id: None,
+            action,
+            moderator_override: false,
+            timestamp: Timestamp::now(),
+        }
+    }
+
+    pub fn without_channel(guild_id: GuildId, user_id: UserId, action: AuditAction) -> Self {
+        Self {
+            guild_id,
+            channel_id: None,
+            user_id,
+            target_id: None,
+            action,
+            moderator_override: false,
+            timestamp: Timestamp::now(),
+        }
+    }
+
+    pub fn target(mut self, id: impl Into<UserId>) -> Self {
+        self.target_id = Some(id.into());
+        self
+    }
+
+    fn embed(&self) -> CreateEmbed {
+        let mut embed = CreateEmbed::new()
+            .title(self.action.title())
+            .timestamp(self.timestamp);
+
+        if let Some(channel) = self.channel_id {
+            embed = embed.field("Channel", channel.mention().to_string(), true);
+        }
+
+        embed = embed.field("User", self.user_id.mention().to_string(), true);
+
+        if let Some(target) = self.target_id {
+            embed = embed.field("Target", target.mention().to_string(), true);
+        }
+
+        if self.moderator_override {
+            embed = embed.field(
+                "Moderator Override",
+                "Used moderator permissions on a channel they don't own.",
+                false,
+            );
+        }
+
+        embed
+    }
+}
+
+pub struct AuditLog;
+
+impl AuditLog {
+    /// Posts the event to the guild's log channel, if one is configured, and
+    /// hands it to the guild manager for persistence.
+    ///
+    /// Logging is best effort and never fails the action being logged.
+    pub async fn record<Db: Database, GuildManager: TempVoiceGuildManager<Db>>(
+        ctx: &Context,
+        pool: &Pool<Db>,
+        event: AuditEvent,
+    ) {
+        let log_channel = GuildManager::get(pool, event.guild_id)
+            .await
+            .ok()
+            .and_then(|row| row.log_channel());
+
+        if let Some(log_channel) = log_channel
+            && let Err(e) = log_channel
+                .send_message(ctx, CreateMessage::new().embed(event.embed()))
+                .await
+        {
+            warn!(error = %e, %log_channel, "failed to post audit event");
+        }
+
+        if let Err(e) = GuildManager::save_audit_event(pool, &event).await {
+            warn!(error = %e, "failed to save audit event");
+        }
+    }
+}
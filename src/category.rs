@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use serenity::all::{
+    ChannelId, ChannelType, Context, CreateChannel, DiscordJsonError, ErrorResponse, GuildChannel,
+    GuildId, HttpError,
+};
+use sqlx::{Database, Pool};
+
+use crate::{Error, Result, TempVoiceGuildManager, TempVoiceRow};
+
+/// Discord refuses to create more than 50 channels in a single category.
+const MAX_CATEGORY_CHANNELS: usize = 50;
+
+/// Finds a temporary voice category with room for another channel, creating a
+/// new overflow category if the guild allows it.
+pub(crate) async fn available_category<Db: Database, Manager: TempVoiceGuildManager<Db>>(
+    ctx: &Context,
+    pool: &Pool<Db>,
+    guild_data: &mut TempVoiceRow,
+) -> Result<ChannelId> {
+    let guild_id = guild_data.guild_id();
+    let channels = guild_channels(ctx, guild_id).await?;
+
+    let category = guild_data
+        .categories()
+        .into_iter()
+        .filter(|category| channels.contains_key(category))
+        .find(|category| children(&channels, *category) < MAX_CATEGORY_CHANNELS);
+
+    if let Some(category) = category {
+        return Ok(category);
+    }
+
+    if !guild_data.auto_overflow() {
+        return Err(Error::CategoryFull);
+    }
+
+    let name = format!("Temp Voice {}", guild_data.categories().len() + 1);
+
+    let category = guild_id
+        .create_channel(ctx, CreateChannel::new(name).kind(ChannelType::Category))
+        .await?;
+
+    guild_data.add_auto_category(category.id);
+    guild_data.clone().save::<Db, Manager>(pool).await?;
+
+    Ok(category.id)
+}
+
+/// Deletes a category the bot created for overflow once its last channel is
+/// gone. `deleted` is left out of the count, as the cache may still hold the
+/// channel that was just deleted.
+pub(crate) async fn cleanup_category<Db: Database, Manager: TempVoiceGuildManager<Db>>(
+    ctx: &Context,
+    pool: &Pool<Db>,
+    mut guild_data: TempVoiceRow,
+    category: ChannelId,
+    deleted: ChannelId,
+) -> Result<()> {
+    if !guild_data.is_auto_category(category) {
+        return Ok(());
+    }
+
+    let mut channels = guild_channels(ctx, guild_data.guild_id()).await?;
+    channels.remove(&deleted);
+
+    if children(&channels, category) > 0 {
+        return Ok(());
+    }
+
+    // The voice state handler and the channel delete event can both get here.
+    match category.delete(ctx).await {
+        Err(serenity::Error::Http(HttpError::UnsuccessfulRequest(ErrorResponse {
+            error: DiscordJsonError { code: 10003, .. },
+            ..
+        }))) => {}
+        result => {
+            result?;
+        }
+    };
+
+    guild_data.remove_category(category);
+    guild_data.save::<Db, Manager>(pool).await?;
+
+    Ok(())
+}
+
+pub(crate) async fn guild_channels(
+    ctx: &Context,
+    guild_id: GuildId,
+) -> Result<HashMap<ChannelId, GuildChannel>> {
+    #[cfg(feature = "cache")]
+    if let Some(guild) = ctx.cache.guild(guild_id) {
+        return Ok(guild.channels.clone().into_iter().collect());
+    }
+
+    Ok(guild_id.channels(ctx).await?)
+}
+
+fn children(channels: &HashMap<ChannelId, GuildChannel>, category: ChannelId) -> usize {
+    channels
+        .values()
+        .filter(|channel| channel.parent_id == Some(category))
+        .count()
+}
@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+
+use serenity::all::{
+    CommandInteraction, Context, EditInteractionResponse, GuildId, Mentionable, ResolvedValue,
+    Timestamp,
+};
+use sqlx::{Database, Pool};
+
+use crate::{
+    AuditAction, AuditEvent, AuditLog, Error, Result, TempVoiceBan, TempVoiceGuildManager,
+};
+
+/// Ten years, well past anything a temporary ban needs.
+pub const MAX_BAN_HOURS: i64 = 24 * 365 * 10;
+
+pub async fn ban<Db: Database, Manager: TempVoiceGuildManager<Db>>(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    pool: &Pool<Db>,
+    mut options: HashMap<&str, ResolvedValue<'_>>,
+    guild_id: GuildId,
+) -> Result<()> {
+    interaction.defer_ephemeral(ctx).await.unwrap();
+
+    let user = match options.remove("user") {
+        Some(ResolvedValue::User(user, _)) => user,
+        _ => unreachable!("User option is required"),
+    };
+
+    let expires_at = match options.remove("hours") {
+        Some(ResolvedValue::Integer(hours)) => {
+            let expires_at = hours
+                .checked_mul(3600)
+                .and_then(|seconds| Timestamp::now().unix_timestamp().checked_add(seconds))
+                .ok_or(Error::DurationTooLong)?;
+
+            Some(Timestamp::from_unix_timestamp(expires_at).map_err(|_| Error::DurationTooLong)?)
+        }
+        _ => None,
+    };
+
+    Manager::save_ban(pool, TempVoiceBan::new(guild_id, user.id, expires_at))
+        .await
+        .unwrap();
+
+    AuditLog::record::<Db, Manager>(
+        ctx,
+        pool,
+        AuditEvent::without_channel(guild_id, interaction.user.id, AuditAction::Ban)
+            .target(user.id),
+    )
+    .await;
+
+    let content = match expires_at {
+        Some(expires_at) => format!(
+            "Banned {} from creating temporary voice channels until <t:{}:f>.",
+            user.mention(),
+            expires_at.unix_timestamp()
+        ),
+        None => format!(
+            "Banned {} from creating temporary voice channels.",
+            user.mention()
+        ),
+    };
+
+    interaction
+        .edit_response(ctx, EditInteractionResponse::new().content(content))
+        .await
+        .unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+
+use serenity::all::{CommandInteraction, Context, EditInteractionResponse, ResolvedValue};
+use sqlx::{Database, Pool};
+
+use crate::{Error, ExpiryAction, Result, TempVoiceGuildManager, TempVoiceRow};
+
+/// Ten years, well past anything an idle channel needs.
+pub const MAX_EXPIRY_DAYS: i64 = 365 * 10;
+
+pub async fn expiry<Db: Database, Manager: TempVoiceGuildManager<Db>>(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    pool: &Pool<Db>,
+    mut options: HashMap<&str, ResolvedValue<'_>>,
+    mut guild_data: TempVoiceRow,
+) -> Result<()> {
+    interaction.defer_ephemeral(ctx).await.unwrap();
+
+    if let Some(ResolvedValue::Integer(days)) = options.remove("days") {
+        let seconds = days
+            .max(0)
+            .checked_mul(24 * 60 * 60)
+            .ok_or(Error::DurationTooLong)?;
+
+        guild_data.set_idle_expiry(seconds);
+    }
+
+    if let Some(ResolvedValue::String(action)) = options.remove("action") {
+        let action = match action {
+            "unpersist" => ExpiryAction::Unpersist,
+            "delete" => ExpiryAction::Delete,
+            _ => unreachable!("Invalid action option"),
+        };
+
+        guild_data.set_expiry_action(action);
+    }
+
+    let content = match guild_data.idle_expiry() {
+        Some(seconds) => {
+            let action = match guild_data.expiry_action() {
+                ExpiryAction::Unpersist => "unpersisted",
+                ExpiryAction::Delete => "deleted",
+            };
+
+            format!(
+                "Persistent channels idle for {} day(s) will be {action}.",
+                seconds / (24 * 60 * 60)
+            )
+        }
+        None => String::from("Persistent channels no longer expire."),
+    };
+
+    guild_data.save::<Db, Manager>(pool).await?;
+
+    interaction
+        .edit_response(ctx, EditInteractionResponse::new().content(content))
+        .await
+        .unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+
+use serenity::all::{CommandInteraction, Context, EditInteractionResponse, ResolvedValue};
+use sqlx::{Database, Pool};
+
+use crate::name_filter::compile_pattern;
+use crate::{Error, Result, TempVoiceGuildManager, TempVoiceRow};
+
+pub async fn filter<Db: Database, Manager: TempVoiceGuildManager<Db>>(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    pool: &Pool<Db>,
+    mut options: HashMap<&str, ResolvedValue<'_>>,
+    mut guild_data: TempVoiceRow,
+) -> Result<()> {
+    interaction.defer_ephemeral(ctx).await.unwrap();
+
+    if let Some(ResolvedValue::String(word)) = options.remove("block") {
+        guild_data.block_name(word);
+    }
+
+    if let Some(ResolvedValue::String(word)) = options.remove("allow") {
+        guild_data.allow_name(word);
+    }
+
+    if let Some(ResolvedValue::String(pattern)) = options.remove("pattern") {
+        compile_pattern(pattern).map_err(Error::InvalidNamePattern)?;
+        guild_data.set_name_pattern(Some(pattern.to_string()));
+    }
+
+    if let Some(ResolvedValue::Boolean(true)) = options.remove("clear_pattern") {
+        guild_data.set_name_pattern(None);
+    }
+
+    let blocklist = match guild_data.name_blocklist() {
+        [] => String::from("None"),
+        words => words
+            .iter()
+            .map(|word| format!("`{word}`"))
+            .collect::<Vec<_>>()
+            .join(", "),
+    };
+
+    let pattern = match guild_data.name_pattern() {
+        Some(pattern) => format!("`{pattern}`"),
+        None => String::from("None"),
+    };
+
+    guild_data.save::<Db, Manager>(pool).await?;
+
+    interaction
+        .edit_response(
+            ctx,
+            EditInteractionResponse::new().content(format!(
+                "Blocked words: {blocklist}\nBlocked pattern: {pattern}"
+            )),
+        )
+        .await
+        .unwrap();
+
+    Ok(())
+}
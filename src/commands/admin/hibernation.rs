@@ -0,0 +1,36 @@
+use std::collections::HashMap;
+
+use serenity::all::{CommandInteraction, Context, EditInteractionResponse, ResolvedValue};
+use sqlx::{Database, Pool};
+
+use crate::{Result, TempVoiceGuildManager, TempVoiceRow};
+
+pub async fn hibernation<Db: Database, Manager: TempVoiceGuildManager<Db>>(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    pool: &Pool<Db>,
+    mut options: HashMap<&str, ResolvedValue<'_>>,
+    mut guild_data: TempVoiceRow,
+) -> Result<()> {
+    interaction.defer_ephemeral(ctx).await.unwrap();
+
+    let Some(ResolvedValue::Boolean(enabled)) = options.remove("enabled") else {
+        unreachable!("Enabled option is required");
+    };
+
+    guild_data.set_hibernate(enabled);
+    guild_data.save::<Db, Manager>(pool).await?;
+
+    let content = if enabled {
+        "Empty persistent channels will now hibernate until their owner restores them."
+    } else {
+        "Persistent channels will no longer hibernate."
+    };
+
+    interaction
+        .edit_response(ctx, EditInteractionResponse::new().content(content))
+        .await
+        .unwrap();
+
+    Ok(())
+}
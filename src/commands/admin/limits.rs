@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+
+use serenity::all::{CommandInteraction, Context, EditInteractionResponse, ResolvedValue};
+use sqlx::{Database, Pool};
+
+use crate::{Result, TempVoiceGuildManager, TempVoiceRow};
+
+/// A week, well past anything a creation cooldown needs.
+pub const MAX_COOLDOWN_SECONDS: i64 = 7 * 24 * 60 * 60;
+
+pub async fn limits<Db: Database, Manager: TempVoiceGuildManager<Db>>(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    pool: &Pool<Db>,
+    mut options: HashMap<&str, ResolvedValue<'_>>,
+    mut guild_data: TempVoiceRow,
+) -> Result<()> {
+    interaction.defer_ephemeral(ctx).await.unwrap();
+
+    if let Some(ResolvedValue::Integer(seconds)) = options.remove("cooldown") {
+        guild_data.set_creation_cooldown(seconds.clamp(0, MAX_COOLDOWN_SECONDS));
+    }
+
+    if let Some(ResolvedValue::Integer(limit)) = options.remove("max_channels") {
+        guild_data.set_max_owned_channels(limit.max(0));
+    }
+
+    let cooldown = match guild_data.creation_cooldown() {
+        Some(seconds) => format!("{seconds} seconds"),
+        None => String::from("disabled"),
+    };
+
+    let max_channels = match guild_data.max_owned_channels() {
+        Some(limit) => limit.to_string(),
+        None => String::from("unlimited"),
+    };
+
+    guild_data.save::<Db, Manager>(pool).await?;
+
+    interaction
+        .edit_response(
+            ctx,
+            EditInteractionResponse::new().content(format!(
+                "Creation cooldown: {cooldown}\nMax channels per user: {max_channels}"
+            )),
+        )
+        .await
+        .unwrap();
+
+    Ok(())
+}
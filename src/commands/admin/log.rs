@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+
+use serenity::all::{
+    CommandInteraction, Context, EditInteractionResponse, Mentionable, ResolvedValue,
+};
+use sqlx::{Database, Pool};
+
+use crate::{Result, TempVoiceGuildManager, TempVoiceRow};
+
+pub async fn log<Db: Database, Manager: TempVoiceGuildManager<Db>>(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    pool: &Pool<Db>,
+    mut options: HashMap<&str, ResolvedValue<'_>>,
+    mut guild_data: TempVoiceRow,
+) -> Result<()> {
+    interaction.defer_ephemeral(ctx).await.unwrap();
+
+    let channel = match options.remove("channel") {
+        Some(ResolvedValue::Channel(channel)) => Some(channel.id),
+        _ => None,
+    };
+
+    guild_data.set_log_channel(channel);
+    guild_data.save::<Db, Manager>(pool).await?;
+
+    let content = match channel {
+        Some(channel) => format!(
+            "Temporary voice logs will be posted in {}.",
+            channel.mention()
+        ),
+        None => String::from("Temporary voice logs will no longer be posted."),
+    };
+
+    interaction
+        .edit_response(ctx, EditInteractionResponse::new().content(content))
+        .await
+        .unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,464 @@
+mod ban;
+mod expiry;
+mod filter;
+mod hibernation;
+mod limits;
+mod log;
+mod moderator;
+mod ordering;
+mod overflow;
+mod premium;
+mod privacy;
+mod quota;
+mod unban;
+mod welcome;
+
+use ban::{MAX_BAN_HOURS, ban};
+use expiry::{MAX_EXPIRY_DAYS, expiry};
+use filter::filter;
+use hibernation::hibernation;
+use limits::{MAX_COOLDOWN_SECONDS, limits};
+use log::log;
+use moderator::moderator;
+use ordering::ordering;
+use overflow::overflow;
+use premium::premium;
+use privacy::privacy;
+use quota::quota;
+use serenity::all::{
+    ChannelType, CommandInteraction, CommandOptionType, Context, CreateCommandOption, GuildId,
+    Member, ResolvedOption, ResolvedValue,
+};
+use sqlx::{Database, Pool};
+use unban::unban;
+use welcome::welcome;
+use zayden_core::parse_options;
+
+use crate::error::PermissionError;
+use crate::metrics::Metrics;
+use crate::{Error, Result, TempVoiceGuildManager, TempVoiceRow};
+
+pub async fn admin<Db: Database, GuildManager: TempVoiceGuildManager<Db>>(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    pool: &Pool<Db>,
+    guild_id: GuildId,
+    mut options: Vec<ResolvedOption<'_>>,
+) -> Result<()> {
+    let command = options.pop().unwrap();
+
+    let options = match command.value {
+        ResolvedValue::SubCommand(options) => parse_options(options),
+        _ => unreachable!("Subcommand is required"),
+    };
+
+    Metrics::get(ctx)
+        .await
+        .command(&format!("admin {}", command.name));
+
+    let member = interaction.member.as_deref().ok_or(Error::MissingGuildId)?;
+    let guild_data = GuildManager::get(pool, guild_id)
+        .await
+        .map_err(|_| Error::NotSetup)?;
+
+    if let Some(required) = missing_permission(member, Some(&guild_data), command.name) {
+        return Err(Error::MissingPermissions(required));
+    }
+
+    match command.name {
+        "moderator" => {
+            moderator::<Db, GuildManager>(ctx, interaction, pool, options, guild_data).await?;
+        }
+        "log" => {
+            log::<Db, GuildManager>(ctx, interaction, pool, options, guild_data).await?;
+        }
+        "limits" => {
+            limits::<Db, GuildManager>(ctx, interaction, pool, options, guild_data).await?;
+        }
+        "filter" => {
+            filter::<Db, GuildManager>(ctx, interaction, pool, options, guild_data).await?;
+        }
+        "overflow" => {
+            overflow::<Db, GuildManager>(ctx, interaction, pool, options, guild_data).await?;
+        }
+        "ordering" => {
+            ordering::<Db, GuildManager>(ctx, interaction, pool, options, guild_data).await?;
+        }
+        "hibernation" => {
+            hibernation::<Db, GuildManager>(ctx, interaction, pool, options, guild_data).await?;
+        }
+        "expiry" => {
+            expiry::<Db, GuildManager>(ctx, interaction, pool, options, guild_data).await?;
+        }
+        "quota" => {
+            quota::<Db, GuildManager>(ctx, interaction, pool, options, guild_data).await?;
+        }
+        "premium" => {
+            premium::<Db, GuildManager>(ctx, interaction, pool, options, guild_data).await?;
+        }
+        "welcome" => {
+            welcome::<Db, GuildManager>(ctx, interaction, pool, options, guild_data).await?;
+        }
+        "privacy" => {
+            privacy::<Db, GuildManager>(ctx, interaction, pool, options, guild_data).await?;
+        }
+        "ban" => {
+            ban::<Db, GuildManager>(ctx, interaction, pool, options, guild_id).await?;
+        }
+        "unban" => {
+            unban::<Db, GuildManager>(ctx, interaction, pool, options, guild_id).await?;
+        }
+        _ => unreachable!("Invalid subcommand name"),
+    }
+
+    Ok(())
+}
+
+/// The permission the member lacks to run `command`, if any. `/voice setup`
+/// goes through here too, before the guild has any data.
+pub(super) fn missing_permission(
+    member: &Member,
+    guild_data: Option<&TempVoiceRow>,
+    command: &str,
+) -> Option<PermissionError> {
+    let required = required_permission(command);
+    let permitted = match required {
+        PermissionError::NotModerator => {
+            guild_data.is_some_and(|guild_data| guild_data.is_moderator(member))
+        }
+        _ => member.permissions.unwrap_or_default().administrator(),
+    };
+
+    (!permitted).then_some(required)
+}
+
+/// Bans are left to moderators; everything else configures the guild and needs
+/// an administrator.
+fn required_permission(command: &str) -> PermissionError {
+    match command {
+        "ban" | "unban" => PermissionError::NotModerator,
+        _ => PermissionError::NotAdministrator,
+    }
+}
+
+pub fn register() -> CreateCommandOption {
+    CreateCommandOption::new(
+        CommandOptionType::SubCommandGroup,
+        "admin",
+        "Manage the temporary voice channel module for the guild.",
+    )
+    .add_sub_option(
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "moderator",
+            "Set the role that can manage every temporary voice channel.",
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::Role,
+            "role",
+            "The moderator role. Leave empty to only use channel permissions.",
+        )),
+    )
+    .add_sub_option(
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "log",
+            "Set the channel temporary voice logs are posted in.",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::Channel,
+                "channel",
+                "The text channel for logs. Leave empty to stop posting logs.",
+            )
+            .channel_types(vec![ChannelType::Text]),
+        ),
+    )
+    .add_sub_option(
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "limits",
+            "Limit how often and how many temporary voice channels users can create.",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "cooldown",
+                "Seconds a user must wait between creating channels (0 to disable).",
+            )
+            .min_int_value(0)
+            .max_int_value(MAX_COOLDOWN_SECONDS as u64),
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "max_channels",
+                "The maximum channels a user can own at once (0 for unlimited).",
+            )
+            .min_int_value(0),
+        ),
+    )
+    .add_sub_option(
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "filter",
+            "Block words or a pattern from temporary voice channel names.",
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "block",
+            "A word to block from channel names.",
+        ))
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "allow",
+            "A previously blocked word to allow again.",
+        ))
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "pattern",
+            "A regular expression that channel names must not match.",
+        ))
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            "clear_pattern",
+            "Remove the blocked pattern.",
+        )),
+    )
+    .add_sub_option(
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "overflow",
+            "Configure extra categories for when the main category is full.",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::Channel,
+                "add",
+                "A category to use once the previous ones are full.",
+            )
+            .channel_types(vec![ChannelType::Category]),
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::Channel,
+                "remove",
+                "An overflow category to stop using.",
+            )
+            .channel_types(vec![ChannelType::Category]),
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            "auto",
+            "Create new categories automatically when every category is full.",
+        )),
+    )
+    .add_sub_option(
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "ordering",
+            "Choose how temporary voice channels are arranged in their category.",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "order",
+                "How to sort temporary voice channels.",
+            )
+            .add_string_choice("Discord Default", "discord")
+            .add_string_choice("Below Creator Channel", "creator")
+            .add_string_choice("Member Count", "members")
+            .add_string_choice("Creation Time", "created"),
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            "pin_persistent",
+            "Keep persistent channels at the top of the category.",
+        )),
+    )
+    .add_sub_option(
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "hibernation",
+            "Archive empty persistent channels until their owner restores them.",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::Boolean,
+                "enabled",
+                "Whether empty persistent channels hibernate.",
+            )
+            .required(true),
+        ),
+    )
+    .add_sub_option(
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "expiry",
+            "Expire persistent channels that haven't been used in a while.",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "days",
+                "Days without activity before a persistent channel expires (0 to disable).",
+            )
+            .min_int_value(0)
+            .max_int_value(MAX_EXPIRY_DAYS as u64),
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "action",
+                "What happens to expired channels.",
+            )
+            .add_string_choice("Make Temporary", "unpersist")
+            .add_string_choice("Delete", "delete"),
+        ),
+    )
+    .add_sub_option(
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "quota",
+            "Set how many persistent channels members can have.",
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::Role,
+            "role",
+            "The role the quota applies to. Leave empty to set the default.",
+        ))
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "quota",
+                "The number of persistent channels (0 for unlimited). Leave empty to remove a role's quota.",
+            )
+            .min_int_value(0),
+        ),
+    )
+    .add_sub_option(
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "premium",
+            "Limit channel features to premium roles, such as server boosters.",
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::Role,
+            "add_role",
+            "A role that can use premium features.",
+        ))
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::Role,
+            "remove_role",
+            "A role that can no longer use premium features.",
+        ))
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "feature",
+                "The feature to change.",
+            )
+            .add_string_choice("High Bitrate", "high_bitrate")
+            .add_string_choice("Persist", "persist")
+            .add_string_choice("Region", "region"),
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            "premium_only",
+            "Whether the feature is limited to premium roles. Defaults to true.",
+        )),
+    )
+    .add_sub_option(
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "welcome",
+            "Post a welcome message in the text chat of new channels.",
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            "enabled",
+            "Whether new channels get a welcome message.",
+        ))
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "message",
+            "The message. {owner}, {channel} and {commands} are filled in, \\n starts a new line.",
+        ))
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            "default_message",
+            "Go back to the default welcome message.",
+        ))
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            "panel",
+            "Attach buttons to change the channel's privacy and promote speakers.",
+        )),
+    )
+    .add_sub_option(super::add_restriction_options(
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "privacy",
+            "Set the privacy new temporary voice channels start with.",
+        )
+        .add_sub_option(super::privacy_option(
+            "The privacy new channels start with.",
+        )),
+    ))
+    .add_sub_option(
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "ban",
+            "Ban a user from creating temporary voice channels.",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(CommandOptionType::User, "user", "The user to ban.")
+                .required(true),
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "hours",
+                "How long the ban lasts. Leave empty for a permanent ban.",
+            )
+            .min_int_value(1)
+            .max_int_value(MAX_BAN_HOURS as u64),
+        ),
+    )
+    .add_sub_option(
+        CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "unban",
+            "Allow a banned user to create temporary voice channels again.",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(CommandOptionType::User, "user", "The user to unban.")
+                .required(true),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bans_need_a_moderator() {
+        for command in ["ban", "unban"] {
+            assert!(matches!(
+                required_permission(command),
+                PermissionError::NotModerator
+            ));
+        }
+    }
+
+    #[test]
+    fn configuration_needs_an_administrator() {
+        for command in ["setup", "log", "limits", "expiry"] {
+            assert!(matches!(
+                required_permission(command),
+                PermissionError::NotAdministrator
+            ));
+        }
+    }
+}
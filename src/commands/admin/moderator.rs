@@ -0,0 +1,36 @@
+use std::collections::HashMap;
+
+use serenity::all::{CommandInteraction, Context, EditInteractionResponse, ResolvedValue};
+use sqlx::{Database, Pool};
+
+use crate::{Result, TempVoiceGuildManager, TempVoiceRow};
+
+pub async fn moderator<Db: Database, Manager: TempVoiceGuildManager<Db>>(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    pool: &Pool<Db>,
+    mut options: HashMap<&str, ResolvedValue<'_>>,
+    mut guild_data: TempVoiceRow,
+) -> Result<()> {
+    interaction.defer_ephemeral(ctx).await.unwrap();
+
+    let role = match options.remove("role") {
+        Some(ResolvedValue::Role(role)) => Some(role.id),
+        _ => None,
+    };
+
+    guild_data.set_moderator_role(role);
+    guild_data.save::<Db, Manager>(pool).await?;
+
+    let content = match role {
+        Some(_) => "Moderator role updated.",
+        None => "Moderator role removed.",
+    };
+
+    interaction
+        .edit_response(ctx, EditInteractionResponse::new().content(content))
+        .await
+        .unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+
+use serenity::all::{CommandInteraction, Context, EditInteractionResponse, ResolvedValue};
+use sqlx::{Database, Pool};
+
+use crate::{ChannelOrder, Result, TempVoiceGuildManager, TempVoiceRow};
+
+pub async fn ordering<Db: Database, Manager: TempVoiceGuildManager<Db>>(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    pool: &Pool<Db>,
+    mut options: HashMap<&str, ResolvedValue<'_>>,
+    mut guild_data: TempVoiceRow,
+) -> Result<()> {
+    interaction.defer_ephemeral(ctx).await.unwrap();
+
+    if let Some(ResolvedValue::String(order)) = options.remove("order") {
+        let order = match order {
+            "discord" => ChannelOrder::Discord,
+            "creator" => ChannelOrder::Creator,
+            "members" => ChannelOrder::Members,
+            "created" => ChannelOrder::Created,
+            _ => unreachable!("Invalid order option"),
+        };
+
+        guild_data.set_channel_order(order);
+    }
+
+    if let Some(ResolvedValue::Boolean(enabled)) = options.remove("pin_persistent") {
+        guild_data.set_pin_persistent(enabled);
+    }
+
+    guild_data.save::<Db, Manager>(pool).await?;
+
+    interaction
+        .edit_response(
+            ctx,
+            EditInteractionResponse::new().content("Channel ordering updated."),
+        )
+        .await
+        .unwrap();
+
+    Ok(())
+}
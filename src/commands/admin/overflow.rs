@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+
+use serenity::all::{
+    CommandInteraction, Context, EditInteractionResponse, Mentionable, ResolvedValue,
+};
+use sqlx::{Database, Pool};
+
+use crate::{Result, TempVoiceGuildManager, TempVoiceRow};
+
+pub async fn overflow<Db: Database, Manager: TempVoiceGuildManager<Db>>(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    pool: &Pool<Db>,
+    mut options: HashMap<&str, ResolvedValue<'_>>,
+    mut guild_data: TempVoiceRow,
+) -> Result<()> {
+    interaction.defer_ephemeral(ctx).await.unwrap();
+
+    if let Some(ResolvedValue::Channel(category)) = options.remove("add") {
+        guild_data.add_overflow_category(category.id);
+    }
+
+    if let Some(ResolvedValue::Channel(category)) = options.remove("remove") {
+        guild_data.remove_category(category.id);
+    }
+
+    if let Some(ResolvedValue::Boolean(enabled)) = options.remove("auto") {
+        guild_data.set_auto_overflow(enabled);
+    }
+
+    let categories = guild_data
+        .categories()
+        .iter()
+        .map(|category| category.mention().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let auto = if guild_data.auto_overflow() {
+        "enabled"
+    } else {
+        "disabled"
+    };
+
+    guild_data.save::<Db, Manager>(pool).await?;
+
+    interaction
+        .edit_response(
+            ctx,
+            EditInteractionResponse::new().content(format!(
+                "Categories: {categories}\nAutomatic overflow categories are {auto}."
+            )),
+        )
+        .await
+        .unwrap();
+
+    Ok(())
+}
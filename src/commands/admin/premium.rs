@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+
+use serenity::all::{
+    CommandInteraction, Context, EditInteractionResponse, Mentionable, ResolvedValue, RoleId,
+};
+use sqlx::{Database, Pool};
+
+use crate::{PremiumFeature, Result, TempVoiceGuildManager, TempVoiceRow};
+
+pub async fn premium<Db: Database, Manager: TempVoiceGuildManager<Db>>(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    pool: &Pool<Db>,
+    mut options: HashMap<&str, ResolvedValue<'_>>,
+    mut guild_data: TempVoiceRow,
+) -> Result<()> {
+    interaction.defer_ephemeral(ctx).await.unwrap();
+
+    if let Some(ResolvedValue::Role(role)) = options.remove("add_role") {
+        guild_data.add_premium_role(role.id);
+    }
+
+    if let Some(ResolvedValue::Role(role)) = options.remove("remove_role") {
+        guild_data.remove_premium_role(role.id);
+    }
+
+    if let Some(ResolvedValue::String(feature)) = options.remove("feature") {
+        let feature = PremiumFeature::from_name(feature).expect("Invalid feature option");
+
+        let gated = match options.remove("premium_only") {
+            Some(ResolvedValue::Boolean(gated)) => gated,
+            _ => true,
+        };
+
+        guild_data.set_premium_feature(feature, gated);
+    }
+
+    let roles = guild_data
+        .temp_voice_premium_roles
+        .iter()
+        .map(|id| RoleId::from(*id as u64).mention().to_string())
+        .collect::<Vec<_>>();
+
+    let roles = if roles.is_empty() {
+        String::from("none")
+    } else {
+        roles.join(", ")
+    };
+
+    let features = if guild_data.temp_voice_premium_features.is_empty() {
+        String::from("none")
+    } else {
+        guild_data.temp_voice_premium_features.join(", ")
+    };
+
+    guild_data.save::<Db, Manager>(pool).await?;
+
+    interaction
+        .edit_response(
+            ctx,
+            EditInteractionResponse::new().content(format!(
+                "Premium roles: {roles}\nPremium features: {features}"
+            )),
+        )
+        .await
+        .unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+
+use serenity::all::{CommandInteraction, Context, EditInteractionResponse, ResolvedValue};
+use sqlx::{Database, Pool};
+
+use crate::privacy::restriction_options;
+use crate::{Error, Result, TempVoiceGuildManager, TempVoiceRow, VoiceChannelMode};
+
+pub async fn privacy<Db: Database, Manager: TempVoiceGuildManager<Db>>(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    pool: &Pool<Db>,
+    mut options: HashMap<&str, ResolvedValue<'_>>,
+    mut guild_data: TempVoiceRow,
+) -> Result<()> {
+    interaction.defer_ephemeral(ctx).await.unwrap();
+
+    if let Some(ResolvedValue::String(privacy)) = options.remove("privacy") {
+        let mode = VoiceChannelMode::from_name(privacy).ok_or(Error::InvalidPrivacy)?;
+        guild_data.set_default_mode(mode);
+    }
+
+    for (restriction, enabled) in restriction_options(&mut options) {
+        guild_data.set_default_restriction(restriction, enabled);
+    }
+
+    let restrictions = guild_data
+        .default_restrictions()
+        .iter()
+        .map(|restriction| restriction.as_str())
+        .collect::<Vec<_>>();
+
+    let content = if restrictions.is_empty() {
+        format!(
+            "New channels will start as `{}`.",
+            guild_data.default_mode().as_str()
+        )
+    } else {
+        format!(
+            "New channels will start as `{}` with `{}`.",
+            guild_data.default_mode().as_str(),
+            restrictions.join("`, `")
+        )
+    };
+
+    guild_data.save::<Db, Manager>(pool).await?;
+
+    interaction
+        .edit_response(ctx, EditInteractionResponse::new().content(content))
+        .await
+        .unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+
+use serenity::all::{CommandInteraction, Context, EditInteractionResponse, ResolvedValue};
+use sqlx::{Database, Pool};
+
+use crate::{Result, TempVoiceGuildManager, TempVoiceRow};
+
+pub async fn quota<Db: Database, Manager: TempVoiceGuildManager<Db>>(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    pool: &Pool<Db>,
+    mut options: HashMap<&str, ResolvedValue<'_>>,
+    mut guild_data: TempVoiceRow,
+) -> Result<()> {
+    interaction.defer_ephemeral(ctx).await.unwrap();
+
+    let quota = match options.remove("quota") {
+        Some(ResolvedValue::Integer(quota)) => Some(quota.max(0)),
+        _ => None,
+    };
+
+    let content = match options.remove("role") {
+        Some(ResolvedValue::Role(role)) => {
+            guild_data.set_role_persist_quota(role.id, quota);
+
+            match quota {
+                Some(0) => format!("Members with {} can persist unlimited channels.", role.name),
+                Some(quota) => {
+                    format!("Members with {} can persist {quota} channel(s).", role.name)
+                }
+                None => format!("Removed the persistent channel quota for {}.", role.name),
+            }
+        }
+        _ => {
+            let quota = quota.unwrap_or(1);
+            guild_data.set_default_persist_quota(quota);
+
+            match quota {
+                0 => String::from("Members can persist unlimited channels."),
+                quota => format!("Members can persist {quota} channel(s)."),
+            }
+        }
+    };
+
+    guild_data.save::<Db, Manager>(pool).await?;
+
+    interaction
+        .edit_response(ctx, EditInteractionResponse::new().content(content))
+        .await
+        .unwrap();
+
+    Ok(())
+}
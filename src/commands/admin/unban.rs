@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+
+use serenity::all::{
+    CommandInteraction, Context, EditInteractionResponse, GuildId, Mentionable, ResolvedValue,
+};
+use sqlx::{Database, Pool};
+
+use crate::{AuditAction, AuditEvent, AuditLog, Result, TempVoiceGuildManager};
+
+pub async fn unban<Db: Database, Manager: TempVoiceGuildManager<Db>>(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    pool: &Pool<Db>,
+    mut options: HashMap<&str, ResolvedValue<'_>>,
+    guild_id: GuildId,
+) -> Result<()> {
+    interaction.defer_ephemeral(ctx).await.unwrap();
+
+    let user = match options.remove("user") {
+        Some(ResolvedValue::User(user, _)) => user,
+        _ => unreachable!("User option is required"),
+    };
+
+    Manager::delete_ban(pool, guild_id, user.id).await.unwrap();
+
+    AuditLog::record::<Db, Manager>(
+        ctx,
+        pool,
+        AuditEvent::without_channel(guild_id, interaction.user.id, AuditAction::Unban)
+            .target(user.id),
+    )
+    .await;
+
+    interaction
+        .edit_response(
+            ctx,
+            EditInteractionResponse::new().content(format!(
+                "{} can create temporary voice channels again.",
+                user.mention()
+            )),
+        )
+        .await
+        .unwrap();
+
+    Ok(())
+}
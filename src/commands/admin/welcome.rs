@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+
+use serenity::all::{CommandInteraction, Context, EditInteractionResponse, ResolvedValue};
+use sqlx::{Database, Pool};
+
+use crate::{Result, TempVoiceGuildManager, TempVoiceRow};
+
+pub async fn welcome<Db: Database, Manager: TempVoiceGuildManager<Db>>(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    pool: &Pool<Db>,
+    mut options: HashMap<&str, ResolvedValue<'_>>,
+    mut guild_data: TempVoiceRow,
+) -> Result<()> {
+    interaction.defer_ephemeral(ctx).await.unwrap();
+
+    if let Some(ResolvedValue::Boolean(enabled)) = options.remove("enabled") {
+        guild_data.set_welcome(enabled);
+    }
+
+    if let Some(ResolvedValue::String(message)) = options.remove("message") {
+        guild_data.set_welcome_message(Some(message.replace("\\n", "\n")));
+    }
+
+    if let Some(ResolvedValue::Boolean(true)) = options.remove("default_message") {
+        guild_data.set_welcome_message(None);
+    }
+
+    if let Some(ResolvedValue::Boolean(enabled)) = options.remove("panel") {
+        guild_data.set_welcome_panel(enabled);
+    }
+
+    let content = if guild_data.welcome() {
+        let panel = if guild_data.welcome_panel() {
+            "with"
+        } else {
+            "without"
+        };
+
+        format!("New channels will be sent a welcome message {panel} a control panel.")
+    } else {
+        String::from("Welcome messages are disabled.")
+    };
+
+    guild_data.save::<Db, Manager>(pool).await?;
+
+    interaction
+        .edit_response(ctx, EditInteractionResponse::new().content(content))
+        .await
+        .unwrap();
+
+    Ok(())
+}
@@ -1,42 +1,72 @@
 use std::collections::HashMap;
 
 use serenity::all::{
-    ChannelId, CommandInteraction, Context, EditChannel, EditInteractionResponse, ResolvedValue,
+    ChannelId, CommandInteraction, Context, EditChannel, EditInteractionResponse, GuildId,
+    PartialGuild, PremiumTier, ResolvedValue,
 };
 
-use crate::error::PermissionError;
-use crate::{Error, VoiceChannelRow};
+use crate::Error;
+
+/// The lowest bitrate Discord accepts.
+pub const MIN_KBPS: i64 = 8;
+
+/// The highest bitrate available to every guild, regardless of boosts.
+pub const MAX_STANDARD_KBPS: i64 = 96;
+
+/// The highest bitrate any guild can unlock.
+pub const MAX_KBPS: i64 = 384;
 
 pub async fn bitrate(
     ctx: &Context,
     interaction: &CommandInteraction,
     mut options: HashMap<&str, ResolvedValue<'_>>,
+    guild_id: GuildId,
     channel_id: ChannelId,
-    row: &VoiceChannelRow,
 ) -> Result<(), Error> {
     interaction.defer_ephemeral(ctx).await.unwrap();
 
-    if !row.is_trusted(interaction.user.id) {
-        return Err(Error::MissingPermissions(PermissionError::NotTrusted));
-    }
-
-    let kbps = match options.remove("kbps") {
-        Some(ResolvedValue::Integer(kbps)) => kbps as u32,
+    let requested = match options.remove("kbps") {
+        Some(ResolvedValue::Integer(kbps)) => kbps,
         _ => unreachable!("Kbps option is required"),
     };
 
+    let guild = guild_id.to_partial_guild(ctx).await?;
+    let max = max_kbps(&guild);
+    let kbps = requested.clamp(MIN_KBPS, max);
+
     channel_id
-        .edit(ctx, EditChannel::new().bitrate(kbps * 1000))
-        .await
-        .unwrap();
+        .edit(ctx, EditChannel::new().bitrate(kbps as u32 * 1000))
+        .await?;
+
+    let content = if kbps < requested {
+        format!("Channel bitrate set to {kbps} kbps, the highest this server's boost level allows.")
+    } else {
+        format!("Channel bitrate set to {kbps} kbps.")
+    };
 
     interaction
-        .edit_response(
-            ctx,
-            EditInteractionResponse::new().content("Channel bitrate updated."),
-        )
+        .edit_response(ctx, EditInteractionResponse::new().content(content))
         .await
         .unwrap();
 
     Ok(())
 }
+
+/// The highest bitrate the guild's boost tier unlocks. Guilds with VIP voice
+/// regions always get the maximum.
+fn max_kbps(guild: &PartialGuild) -> i64 {
+    if guild
+        .features
+        .iter()
+        .any(|feature| feature == "VIP_REGIONS")
+    {
+        return MAX_KBPS;
+    }
+
+    match guild.premium_tier {
+        PremiumTier::Tier0 => MAX_STANDARD_KBPS,
+        PremiumTier::Tier1 => 128,
+        PremiumTier::Tier2 => 256,
+        _ => MAX_KBPS,
+    }
+}
@@ -6,8 +6,7 @@ use serenity::all::{
 };
 use sqlx::{Database, Pool};
 
-use crate::error::PermissionError;
-use crate::{Error, VoiceChannelRow, VoiceChannelManager};
+use crate::{Error, VoiceChannelManager, VoiceChannelRow};
 
 pub async fn block<Db: Database, Manager: VoiceChannelManager<Db>>(
     ctx: &Context,
@@ -20,10 +19,6 @@ pub async fn block<Db: Database, Manager: VoiceChannelManager<Db>>(
 ) -> Result<(), Error> {
     interaction.defer_ephemeral(ctx).await.unwrap();
 
-    if !row.is_trusted(interaction.user.id) {
-        return Err(Error::MissingPermissions(PermissionError::NotTrusted));
-    }
-
     let user = match options.remove("user") {
         Some(ResolvedValue::User(user, _)) => user,
         _ => unreachable!("User option is required"),
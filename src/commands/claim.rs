@@ -1,17 +1,20 @@
-use serenity::all::{ChannelId, EditInteractionResponse};
+use serenity::all::{ChannelId, EditInteractionResponse, GuildId, Mentionable};
 use serenity::all::{
     CommandInteraction, Context, PermissionOverwrite, PermissionOverwriteType, Permissions,
 };
 use sqlx::{Database, Pool};
 
+use crate::notifier::{Notification, notify};
 use crate::{Error, VoiceChannelManager, VoiceChannelRow, VoiceStateCache};
 
 pub async fn claim<Db: Database, Manager: VoiceChannelManager<Db>>(
     ctx: &Context,
     interaction: &CommandInteraction,
     pool: &Pool<Db>,
+    guild_id: GuildId,
     channel_id: ChannelId,
     row: Option<VoiceChannelRow>,
+    is_moderator: bool,
 ) -> Result<(), Error> {
     interaction.defer_ephemeral(ctx).await.unwrap();
 
@@ -23,13 +26,15 @@ pub async fn claim<Db: Database, Manager: VoiceChannelManager<Db>>(
 
             row
         }
-        None => VoiceChannelRow::new(channel_id, interaction.user.id),
+        None => VoiceChannelRow::new(channel_id, guild_id, interaction.user.id),
     };
 
-    if !row.is_persistent() && is_claimable(ctx, &row).await {
+    if !row.is_persistent() && !is_moderator && is_claimable(ctx, guild_id, &row).await {
         return Err(Error::OwnerInChannel);
     }
 
+    let previous_owner = row.owner_id();
+
     row.set_owner(interaction.user.id);
     row.save::<Db, Manager>(pool).await?;
 
@@ -45,6 +50,22 @@ pub async fn claim<Db: Database, Manager: VoiceChannelManager<Db>>(
         .await
         .unwrap();
 
+    if previous_owner != interaction.user.id {
+        notify(
+            ctx,
+            Notification::new(
+                previous_owner,
+                format!(
+                    "{} has claimed ownership of {}.",
+                    interaction.user.mention(),
+                    channel_id.mention()
+                ),
+            )
+            .channel(channel_id),
+        )
+        .await;
+    }
+
     interaction
         .edit_response(
             ctx,
@@ -56,16 +77,9 @@ pub async fn claim<Db: Database, Manager: VoiceChannelManager<Db>>(
     Ok(())
 }
 
-async fn is_claimable(ctx: &Context, channel_data: &VoiceChannelRow) -> bool {
-    let data = ctx.data.read().await;
-
-    let owner_state = {
-        let cache = data
-            .get::<VoiceStateCache>()
-            .expect("Expected VoiceStateCache in TypeMap");
-
-        cache.get(&channel_data.owner_id())
-    };
-
-    owner_state.and_then(|state| state.channel_id) == Some(channel_data.channel_id())
+async fn is_claimable(ctx: &Context, guild_id: GuildId, channel_data: &VoiceChannelRow) -> bool {
+    VoiceStateCache::get(ctx)
+        .await
+        .user_channel(guild_id, channel_data.owner_id())
+        == Some(channel_data.channel_id())
 }
@@ -1,15 +1,19 @@
 use std::collections::HashMap;
 
-use serenity::all::{
-    ChannelType, Context, CreateChannel, GuildId, PermissionOverwrite, PermissionOverwriteType,
-    Permissions, ResolvedValue,
-};
+use serenity::all::{ChannelType, Context, CreateChannel, GuildId, Mentionable, ResolvedValue};
 use serenity::all::{DiscordJsonError, EditInteractionResponse, ErrorResponse, HttpError};
 use sqlx::{Database, Pool};
 
+use crate::category::available_category;
+use crate::metrics::{Metrics, timed};
+use crate::name_filter::{filter_name, sanitise_name};
+use crate::notifier::{Notification, notify};
+use crate::ordering::reorder_category;
+use crate::privacy::{new_channel_overwrites, restriction_options};
+use crate::welcome::send_welcome;
 use crate::{
-    Error, TempVoiceGuildManager, VoiceChannelManager, VoiceChannelRow,
-    delete_voice_channel_if_inactive,
+    AuditAction, AuditEvent, AuditLog, CreationLimits, Error, TempVoiceBan, TempVoiceGuildManager,
+    VoiceChannelManager, VoiceChannelMode, VoiceChannelRow, spawn_grace_period,
 };
 
 pub async fn create<
@@ -25,9 +29,38 @@ pub async fn create<
 ) -> Result<(), Error> {
     interaction.defer_ephemeral(ctx).await.unwrap();
 
+    if let Some(ban) =
+        TempVoiceBan::get_active::<Db, GuildManager>(pool, guild_id, interaction.user.id).await?
+    {
+        return Err(Error::Banned(ban.expires_at));
+    }
+
+    let mut guild_data = GuildManager::get(pool, guild_id)
+        .await
+        .map_err(|_| Error::NotSetup)?;
+
+    CreationLimits::check::<Db, GuildManager, ChannelManager>(
+        ctx,
+        pool,
+        &guild_data,
+        interaction.user.id,
+    )
+    .await?;
+
     let name = match options.remove("name") {
-        Some(ResolvedValue::String(name)) => name.to_string(),
-        _ => format!("{}'s Channel", interaction.user.name),
+        Some(ResolvedValue::String(name)) => {
+            filter_name(ctx, Some(&guild_data), guild_id, name.to_string()).await?
+        }
+        _ => {
+            sanitise_name(
+                ctx,
+                Some(&guild_data),
+                guild_id,
+                format!("{}'s Channel", interaction.user.name),
+                "Voice Channel",
+            )
+            .await
+        }
     };
 
     let limit = match options.remove("limit") {
@@ -35,42 +68,25 @@ pub async fn create<
         _ => 0,
     };
 
-    let privacy = match options.remove("privacy") {
-        Some(ResolvedValue::String(privacy)) => privacy,
-        _ => "visible",
+    let mode = match options.remove("privacy") {
+        Some(ResolvedValue::String(privacy)) => {
+            VoiceChannelMode::from_name(privacy).ok_or(Error::InvalidPrivacy)?
+        }
+        _ => guild_data.default_mode(),
     };
 
-    let mut perms = vec![PermissionOverwrite {
-        allow: Permissions::all(),
-        deny: Permissions::empty(),
-        kind: PermissionOverwriteType::Member(interaction.user.id),
-    }];
-
-    match privacy {
-        "lock" => perms.push(PermissionOverwrite {
-            allow: Permissions::empty(),
-            deny: Permissions::CONNECT,
-            kind: PermissionOverwriteType::Role(guild_id.everyone_role()),
-        }),
-        "unlock" => perms.push(PermissionOverwrite {
-            allow: Permissions::CONNECT,
-            deny: Permissions::empty(),
-            kind: PermissionOverwriteType::Role(guild_id.everyone_role()),
-        }),
-        "invisible" => perms.push(PermissionOverwrite {
-            allow: Permissions::empty(),
-            deny: Permissions::VIEW_CHANNEL,
-            kind: PermissionOverwriteType::Role(guild_id.everyone_role()),
-        }),
-        "visible" => perms.push(PermissionOverwrite {
-            allow: Permissions::VIEW_CHANNEL,
-            deny: Permissions::empty(),
-            kind: PermissionOverwriteType::Role(guild_id.everyone_role()),
-        }),
-        _ => unreachable!("Invalid privacy option"),
-    };
+    let mut restrictions = guild_data.default_restrictions();
+    for (restriction, enabled) in restriction_options(&mut options) {
+        restrictions.retain(|r| *r != restriction);
+
+        if enabled {
+            restrictions.push(restriction);
+        }
+    }
+
+    let perms = new_channel_overwrites(guild_id, interaction.user.id, mode, &restrictions);
 
-    let category = GuildManager::get_category(pool, guild_id).await.unwrap();
+    let category = available_category::<Db, GuildManager>(ctx, pool, &mut guild_data).await?;
 
     let vc_builder = CreateChannel::new(name)
         .kind(ChannelType::Voice)
@@ -78,9 +94,21 @@ pub async fn create<
         .user_limit(limit)
         .permissions(perms);
 
-    let vc = guild_id.create_channel(ctx, vc_builder).await.unwrap();
+    let vc = timed(
+        ctx,
+        "create_channel",
+        guild_id.create_channel(ctx, vc_builder),
+    )
+    .await?;
 
-    let move_result = guild_id.move_member(ctx, interaction.user.id, vc.id).await;
+    CreationLimits::record::<Db, GuildManager>(ctx, pool, guild_id, interaction.user.id).await?;
+
+    let move_result = timed(
+        ctx,
+        "move_member",
+        guild_id.move_member(ctx, interaction.user.id, vc.id),
+    )
+    .await;
 
     let response_content = match move_result {
         Ok(_) => "Voice channel created and you have been moved successfully.",
@@ -96,18 +124,52 @@ pub async fn create<
         .unwrap();
 
     // Target user is not connected to voice.
-    if let Err(serenity::Error::Http(HttpError::UnsuccessfulRequest(ErrorResponse {
-        error: DiscordJsonError { code: 40032, .. },
-        ..
-    }))) = move_result
-    {
-        if delete_voice_channel_if_inactive(ctx, guild_id, interaction.user.id, &vc).await {
-            return Ok(());
-        }
-    }
-
-    let row = VoiceChannelRow::new(vc.id, interaction.user.id);
+    let grace_period = matches!(
+        move_result,
+        Err(serenity::Error::Http(HttpError::UnsuccessfulRequest(
+            ErrorResponse {
+                error: DiscordJsonError { code: 40032, .. },
+                ..
+            }
+        )))
+    );
+
+    let mut row = VoiceChannelRow::new(vc.id, guild_id, interaction.user.id);
+    row.set_mode(mode);
+    row.set_restrictions(&restrictions);
     row.save::<Db, ChannelManager>(pool).await?;
 
+    Metrics::channel_created::<Db, ChannelManager>(ctx, pool, guild_id).await;
+
+    send_welcome(ctx, &guild_data, vc.id, interaction.user.id).await;
+
+    AuditLog::record::<Db, GuildManager>(
+        ctx,
+        pool,
+        AuditEvent::new(guild_id, vc.id, interaction.user.id, AuditAction::Create),
+    )
+    .await;
+
+    reorder_category::<Db, ChannelManager>(ctx, pool, &guild_data, category).await;
+
+    if grace_period {
+        // The ephemeral response is easy to miss, so remind them where to go.
+        notify(
+            ctx,
+            Notification::new(
+                interaction.user.id,
+                format!(
+                    "Voice channel {} created. You have 1 minute to join.",
+                    vc.mention()
+                ),
+            )
+            .channel(vc.id)
+            .interaction(interaction),
+        )
+        .await;
+
+        spawn_grace_period(ctx, guild_id, interaction.user.id, vc);
+    }
+
     Ok(())
 }
@@ -1,8 +1,8 @@
 use serenity::all::{ChannelId, CommandInteraction, Context, EditInteractionResponse};
 use sqlx::{Database, Pool};
 
-use crate::error::PermissionError;
-use crate::{Error, VoiceChannelRow, VoiceChannelManager};
+use crate::metrics::{Metrics, timed};
+use crate::{Error, VoiceChannelManager, VoiceChannelRow};
 
 pub async fn delete<Db: Database, Manager: VoiceChannelManager<Db>>(
     ctx: &Context,
@@ -13,13 +13,13 @@ pub async fn delete<Db: Database, Manager: VoiceChannelManager<Db>>(
 ) -> Result<(), Error> {
     interaction.defer_ephemeral(ctx).await.unwrap();
 
-    if row.is_owner(interaction.user.id) {
-        return Err(Error::MissingPermissions(PermissionError::NotOwner));
-    }
+    let guild_id = row.guild_id();
 
+    // Keep the row if Discord refuses, so the channel can still be managed.
+    timed(ctx, "delete_channel", channel_id.delete(ctx)).await?;
     row.delete::<Db, Manager>(pool).await?;
 
-    channel_id.delete(ctx).await.unwrap();
+    Metrics::channel_deleted::<Db, Manager>(ctx, pool, guild_id).await;
 
     interaction
         .edit_response(
@@ -1,11 +1,12 @@
 use std::collections::HashMap;
 
+use serenity::all::Mentionable;
 use serenity::all::{
     ChannelId, CommandInteraction, Context, EditInteractionResponse, PermissionOverwrite,
     PermissionOverwriteType, Permissions, ResolvedValue,
 };
-use serenity::all::{CreateMessage, Mentionable};
 
+use crate::notifier::{Delivery, Notification, notify};
 use crate::{Error, VoiceChannelRow};
 
 pub async fn invite(
@@ -36,19 +37,20 @@ pub async fn invite(
         .await
         .unwrap();
 
-    let result = user
-        .direct_message(
-            ctx,
-            CreateMessage::new().content(format!(
-                "You have been invited to {}.",
-                channel_id.mention()
-            )),
+    let delivery = notify(
+        ctx,
+        Notification::new(
+            user.id,
+            format!("You have been invited to {}.", channel_id.mention()),
         )
-        .await;
-
-    let content = match result {
-        Ok(_) => "Sent invite to user.",
-        Err(_) => "Invited user, but failed to send DM.",
+        .channel(channel_id),
+    )
+    .await;
+
+    let content = match delivery {
+        Delivery::DirectMessage | Delivery::FollowUp => "Sent invite to user.",
+        Delivery::Channel => "Invited user and mentioned them in the channel chat.",
+        Delivery::Failed => "Invited user, but failed to notify them.",
     };
 
     interaction
@@ -0,0 +1,50 @@
+use serenity::all::{
+    CommandInteraction, Context, CreateEmbed, EditInteractionResponse, GuildId, Mentionable,
+};
+use sqlx::{Database, Pool};
+
+use crate::{Result, VoiceStatsManager};
+
+const LEADERBOARD_SIZE: i64 = 10;
+
+pub async fn leaderboard<Db: Database, StatsManager: VoiceStatsManager<Db>>(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    pool: &Pool<Db>,
+    guild_id: GuildId,
+) -> Result<()> {
+    interaction.defer_ephemeral(ctx).await.unwrap();
+
+    let leaderboard = StatsManager::leaderboard(pool, guild_id, LEADERBOARD_SIZE)
+        .await
+        .unwrap();
+
+    let description = if leaderboard.is_empty() {
+        String::from("Nobody has spent time in a temporary voice channel yet.")
+    } else {
+        leaderboard
+            .iter()
+            .enumerate()
+            .map(|(i, stats)| {
+                format!(
+                    "{}. {} - {} minutes",
+                    i + 1,
+                    stats.user_id().mention(),
+                    stats.total_minutes()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let embed = CreateEmbed::new()
+        .title("Voice Leaderboard")
+        .description(description);
+
+    interaction
+        .edit_response(ctx, EditInteractionResponse::new().embed(embed))
+        .await
+        .unwrap();
+
+    Ok(())
+}
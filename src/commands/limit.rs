@@ -4,22 +4,16 @@ use serenity::all::{
     ChannelId, CommandInteraction, Context, EditChannel, EditInteractionResponse, ResolvedValue,
 };
 
-use crate::error::PermissionError;
-use crate::{Error, VoiceChannelRow};
+use crate::Error;
 
 pub async fn limit(
     ctx: &Context,
     interaction: &CommandInteraction,
     mut options: HashMap<&str, ResolvedValue<'_>>,
     channel_id: ChannelId,
-    row: &VoiceChannelRow,
 ) -> Result<(), Error> {
     interaction.defer_ephemeral(ctx).await.unwrap();
 
-    if !row.is_trusted(interaction.user.id) {
-        return Err(Error::MissingPermissions(PermissionError::NotTrusted));
-    }
-
     let limit = match options.remove("user_limit") {
         Some(ResolvedValue::Integer(limit)) => limit.clamp(0, 99) as u32,
         _ => 0,
@@ -1,3 +1,4 @@
+mod admin;
 mod bitrate;
 mod block;
 mod claim;
@@ -6,6 +7,7 @@ mod delete;
 mod invite;
 mod join;
 mod kick;
+mod leaderboard;
 mod limit;
 mod name;
 mod password;
@@ -13,13 +15,20 @@ mod persist;
 mod privacy;
 mod region;
 mod reset;
+mod restore;
 mod setup;
+mod speaker;
+mod stats;
 mod transfer;
 mod trust;
 mod unblock;
 mod untrust;
 
-use bitrate::bitrate;
+use std::collections::HashMap;
+
+use admin::admin;
+pub(crate) use bitrate::MAX_STANDARD_KBPS;
+use bitrate::{MAX_KBPS, MIN_KBPS, bitrate};
 use block::block;
 use claim::claim;
 use create::create;
@@ -27,6 +36,7 @@ use delete::delete;
 use invite::invite;
 use join::join;
 use kick::kick;
+use leaderboard::leaderboard;
 use limit::limit;
 use name::name;
 use password::password;
@@ -34,21 +44,29 @@ use persist::persist;
 use privacy::privacy;
 use region::region;
 use reset::reset;
+use restore::restore;
 use setup::setup;
+use speaker::speaker;
 use sqlx::{Database, Pool};
+use stats::stats;
 use transfer::transfer;
 use trust::trust;
 use unblock::unblock;
 use untrust::untrust;
 
 use serenity::all::{
-    CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption,
-    DiscordJsonError, ErrorResponse, HttpError, ResolvedValue,
+    ChannelType, CommandInteraction, CommandOptionType, Context, CreateCommand,
+    CreateCommandOption, DiscordJsonError, ErrorResponse, HttpError, ResolvedValue,
 };
+use tracing::{Instrument, Span, field, info_span, warn};
 use zayden_core::parse_options;
 
+use crate::error::PermissionError;
+use crate::metrics::Metrics;
 use crate::{
-    Error, Result, VoiceChannelManager, get_voice_state, guild_manager::TempVoiceGuildManager,
+    AuditAction, AuditEvent, AuditLog, ChannelRestriction, Error, PremiumFeature, Result,
+    VoiceChannelManager, VoiceChannelMode, VoiceChannelRow, VoiceStatsManager, get_voice_state,
+    guild_manager::TempVoiceGuildManager,
 };
 
 pub struct VoiceCommand;
@@ -58,6 +76,41 @@ impl VoiceCommand {
         Db: Database,
         GuildManager: TempVoiceGuildManager<Db>,
         ChannelManager: VoiceChannelManager<Db>,
+        StatsManager: VoiceStatsManager<Db>,
+    >(
+        ctx: &Context,
+        interaction: &CommandInteraction,
+        pool: &Pool<Db>,
+    ) -> Result<()> {
+        let span = info_span!(
+            "voice_command",
+            guild_id = ?interaction.guild_id,
+            channel_id = field::Empty,
+            user_id = %interaction.user.id,
+            subcommand = field::Empty,
+        );
+
+        let result = Self::run_subcommand::<Db, GuildManager, ChannelManager, StatsManager>(
+            ctx,
+            interaction,
+            pool,
+        )
+        .instrument(span.clone())
+        .await;
+
+        if let Err(e) = &result {
+            span.in_scope(|| warn!(error = ?e, "voice command failed"));
+            Metrics::get(ctx).await.error(e);
+        }
+
+        result
+    }
+
+    async fn run_subcommand<
+        Db: Database,
+        GuildManager: TempVoiceGuildManager<Db>,
+        ChannelManager: VoiceChannelManager<Db>,
+        StatsManager: VoiceStatsManager<Db>,
     >(
         ctx: &Context,
         interaction: &CommandInteraction,
@@ -66,9 +119,18 @@ impl VoiceCommand {
         let guild_id = interaction.guild_id.ok_or(Error::MissingGuildId)?;
 
         let command = interaction.data.options().pop().unwrap();
+        Span::current().record("subcommand", command.name);
 
         let mut options = match command.value {
-            ResolvedValue::SubCommand(options) => parse_options(options),
+            ResolvedValue::SubCommand(options) => {
+                Metrics::get(ctx).await.command(command.name);
+                parse_options(options)
+            }
+            ResolvedValue::SubCommandGroup(options) => {
+                admin::<Db, GuildManager>(ctx, interaction, pool, guild_id, options).await?;
+
+                return Ok(());
+            }
             _ => unreachable!("Subcommand is required"),
         };
 
@@ -90,6 +152,22 @@ impl VoiceCommand {
 
                 return Ok(());
             }
+            "restore" => {
+                restore::<Db, GuildManager, ChannelManager>(ctx, interaction, pool, guild_id)
+                    .await?;
+
+                return Ok(());
+            }
+            "stats" => {
+                stats::<Db, StatsManager>(ctx, interaction, pool, options, guild_id).await?;
+
+                return Ok(());
+            }
+            "leaderboard" => {
+                leaderboard::<Db, StatsManager>(ctx, interaction, pool, guild_id).await?;
+
+                return Ok(());
+            }
             _ => {}
         }
 
@@ -101,35 +179,116 @@ impl VoiceCommand {
                 Err(serenity::Error::Http(HttpError::UnsuccessfulRequest(ErrorResponse {
                     error: DiscordJsonError { code: 10065, .. },
                     ..
-                }))) => return Ok(()),
+                }))) => {
+                    warn!("voice state unknown, ignoring command");
+                    return Ok(());
+                }
                 r => r?.channel_id.ok_or(Error::MemberNotInVoiceChannel)?,
             },
         };
+        Span::current().record("channel_id", channel_id.get());
+
+        let mut audit_event = AuditAction::from_command(command.name)
+            .map(|action| AuditEvent::new(guild_id, channel_id, interaction.user.id, action));
+
+        if let (Some(event), Some(ResolvedValue::User(user, _))) =
+            (audit_event.as_mut(), options.get("user"))
+        {
+            event.target_id = Some(user.id);
+        }
+
+        let guild_data = GuildManager::get(pool, guild_id).await.ok();
+
+        let is_moderator = match (interaction.member.as_deref(), guild_data.as_ref()) {
+            (Some(member), Some(guild_data)) => guild_data.is_moderator(member),
+            _ => false,
+        };
 
         let row = ChannelManager::get(pool, channel_id).await.unwrap();
 
         if command.name == "claim" {
-            claim::<Db, ChannelManager>(ctx, interaction, pool, channel_id, row).await?;
+            claim::<Db, ChannelManager>(
+                ctx,
+                interaction,
+                pool,
+                guild_id,
+                channel_id,
+                row,
+                is_moderator,
+            )
+            .await?;
+
+            if let Some(event) = audit_event {
+                AuditLog::record::<Db, GuildManager>(ctx, pool, event).await;
+            }
+
             return Ok(());
         }
 
         let row = row.ok_or(Error::ChannelNotFound(channel_id))?;
 
+        if let Some(required) = required_permission(command.name) {
+            let permitted = match required {
+                PermissionError::NotOwner => row.is_owner(interaction.user.id),
+                _ => row.is_trusted(interaction.user.id),
+            };
+
+            if !permitted {
+                if !is_moderator {
+                    return Err(Error::MissingPermissions(required));
+                }
+
+                if let Some(event) = audit_event.as_mut() {
+                    event.moderator_override = true;
+                }
+            }
+        }
+
+        if let (Some(feature), Some(guild_data)) = (
+            premium_feature(command.name, &options, &row),
+            guild_data.as_ref(),
+        ) && guild_data.is_premium_feature(feature)
+            && !is_moderator
+            && !interaction
+                .member
+                .as_deref()
+                .is_some_and(|member| guild_data.is_premium(member))
+        {
+            return Err(Error::PremiumFeature(feature));
+        }
+
         match command.name {
             "join" => {
                 join(ctx, interaction, options, guild_id, channel_id, &row).await?;
             }
             "persist" => {
-                persist::<Db, ChannelManager>(ctx, interaction, pool, row).await?;
+                persist::<Db, ChannelManager>(
+                    ctx,
+                    interaction,
+                    pool,
+                    row,
+                    guild_data.as_ref(),
+                    is_moderator,
+                )
+                .await?;
             }
             "name" => {
-                name(ctx, interaction, options, channel_id, &row).await?;
+                name(
+                    ctx,
+                    interaction,
+                    options,
+                    guild_id,
+                    channel_id,
+                    guild_data.as_ref(),
+                )
+                .await?;
             }
             "limit" => {
-                limit(ctx, interaction, options, channel_id, &row).await?;
+                limit(ctx, interaction, options, channel_id).await?;
             }
             "privacy" => {
-                privacy(ctx, interaction, options, guild_id, channel_id, row).await?;
+                privacy::<Db, ChannelManager>(ctx, interaction, pool, options, guild_id, row)
+                    .await?;
             }
             "waiting" => {
                 // waiting(ctx, interaction, guild_id, options).await?;
@@ -146,10 +305,10 @@ impl VoiceCommand {
                 invite(ctx, interaction, options, channel_id, row).await?;
             }
             "kick" => {
-                kick(ctx, interaction, options, guild_id, &row).await?;
+                kick(ctx, interaction, options, guild_id).await?;
             }
             "region" => {
-                region(ctx, interaction, options, channel_id, &row).await?;
+                region(ctx, interaction, options, channel_id).await?;
             }
             "block" => {
                 block::<Db, ChannelManager>(
@@ -164,44 +323,54 @@ impl VoiceCommand {
                 .await?;
             }
             "unblock" => {
-                unblock(ctx, interaction, options, channel_id, &row).await?;
+                unblock(ctx, interaction, options, channel_id).await?;
             }
             "delete" => {
                 delete::<Db, ChannelManager>(ctx, interaction, pool, channel_id, row).await?;
             }
             "bitrate" => {
-                bitrate(ctx, interaction, options, channel_id, &row).await?;
+                bitrate(ctx, interaction, options, guild_id, channel_id).await?;
             }
             "info" => {
                 // info(ctx, interaction, guild_id, options).await?;
             }
             "password" => {
-                password::<Db, ChannelManager>(
+                password::<Db, ChannelManager>(ctx, interaction, pool, options, guild_id, row)
+                    .await?;
+            }
+            "reset" => {
+                reset::<Db, ChannelManager>(
                     ctx,
                     interaction,
                     pool,
-                    options,
                     guild_id,
                     channel_id,
                     row,
+                    guild_data.as_ref(),
                 )
                 .await?;
             }
-            "reset" => {
-                reset::<Db, ChannelManager>(ctx, interaction, pool, guild_id, channel_id, row)
-                    .await?;
-            }
             "transfer" => {
                 transfer::<Db, ChannelManager>(ctx, interaction, pool, options, channel_id, row)
                     .await?;
             }
+            "speaker" => {
+                speaker::<Db, ChannelManager>(ctx, interaction, pool, options, channel_id, row)
+                    .await?;
+            }
             _ => unreachable!("Invalid subcommand name"),
         };
 
+        if let Some(event) = audit_event {
+            AuditLog::record::<Db, GuildManager>(ctx, pool, event).await;
+        }
+
         Ok(())
     }
 
     pub fn register() -> CreateCommand {
+        let admin = admin::register();
+
         let setup = CreateCommandOption::new(
             CommandOptionType::SubCommand,
             "setup",
@@ -214,6 +383,14 @@ impl VoiceCommand {
                 "The category to create temporary voice channels in.",
             )
             .required(true),
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::Channel,
+                "log_channel",
+                "The channel to post temporary voice logs in.",
+            )
+            .channel_types(vec![ChannelType::Text]),
         );
 
         let create = CreateCommandOption::new(
@@ -231,24 +408,36 @@ impl VoiceCommand {
             "limit",
             "The user limit of the voice channel (0-99).",
         ))
-        .add_sub_option(
-            CreateCommandOption::new(
-                CommandOptionType::String,
-                "privacy",
-                "Lock or hide the voice channel.",
-            )
-            .add_string_choice("Spectator", "spectator")
-            .add_string_choice("Open Mic", "open-mic")
-            .add_string_choice("Lock", "lock")
-            .add_string_choice("Unlock", "unlock")
-            .add_string_choice("Invisible", "invisible")
-            .add_string_choice("Visible", "visible"),
-        );
+        .add_sub_option(privacy_option("Lock or hide the voice channel."));
+        let create = add_restriction_options(create);
 
         CreateCommand::new("voice")
             .description("Commands for creating and managing temporary voice channels.")
             .add_option(setup)
+            .add_option(admin)
             .add_option(create)
+            .add_option(CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "restore",
+                "Restore your hibernated voice channels.",
+            ))
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::SubCommand,
+                    "stats",
+                    "See how much time a user has spent in temporary voice channels.",
+                )
+                .add_sub_option(CreateCommandOption::new(
+                    CommandOptionType::User,
+                    "user",
+                    "The user to see stats for. Defaults to you.",
+                )),
+            )
+            .add_option(CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "leaderboard",
+                "See who has spent the most time in temporary voice channels.",
+            ))
             .add_option(
                 CreateCommandOption::new(
                     CommandOptionType::SubCommand,
@@ -279,25 +468,14 @@ impl VoiceCommand {
                     .required(true),
                 ),
             )
-            .add_option(
+            .add_option(add_restriction_options(
                 CreateCommandOption::new(
                     CommandOptionType::SubCommand,
                     "privacy",
                     "Change the privacy of the voice channel.",
                 )
-                .add_sub_option(
-                    CreateCommandOption::new(
-                        CommandOptionType::String,
-                        "privacy",
-                        "The new privacy of the voice channel.",
-                    )
-                    .add_string_choice("Open", "open")
-                    .add_string_choice("Spectator", "spectator")
-                    .add_string_choice("Lock", "lock")
-                    .add_string_choice("Invisible", "invisible")
-                    .required(true),
-                ),
-            )
+                .add_sub_option(privacy_option("The new privacy of the voice channel.")),
+            ))
             // .add_option(CreateCommandOption::new(
             //     CommandOptionType::SubCommand,
             //     "waiting",
@@ -429,6 +607,21 @@ impl VoiceCommand {
                     .required(true),
                 ),
             )
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::SubCommand,
+                    "speaker",
+                    "Let a spectator speak in the voice channel.",
+                )
+                .add_sub_option(
+                    CreateCommandOption::new(
+                        CommandOptionType::User,
+                        "user",
+                        "The spectator to promote.",
+                    )
+                    .required(true),
+                ),
+            )
             .add_option(CreateCommandOption::new(
                 CommandOptionType::SubCommand,
                 "delete",
@@ -446,6 +639,8 @@ impl VoiceCommand {
                         "kbps",
                         "The new bitrate of the voice channel.",
                     )
+                    .min_int_value(MIN_KBPS as u64)
+                    .max_int_value(MAX_KBPS as u64)
                     .required(true),
                 ),
             )
@@ -511,3 +706,51 @@ impl VoiceCommand {
             )
     }
 }
+
+/// The privacy choices offered everywhere a channel's privacy can be set.
+fn privacy_option(description: &str) -> CreateCommandOption {
+    CreateCommandOption::new(CommandOptionType::String, "privacy", description)
+        .add_string_choice("Open", VoiceChannelMode::Open.as_str())
+        .add_string_choice("Spectator", VoiceChannelMode::Spectator.as_str())
+        .add_string_choice("Lock", VoiceChannelMode::Locked.as_str())
+        .add_string_choice("Invisible", VoiceChannelMode::Invisible.as_str())
+}
+
+fn add_restriction_options(mut option: CreateCommandOption) -> CreateCommandOption {
+    for restriction in ChannelRestriction::ALL {
+        option = option.add_sub_option(CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            restriction.as_str(),
+            restriction.description(),
+        ));
+    }
+
+    option
+}
+
+fn required_permission(command: &str) -> Option<PermissionError> {
+    match command {
+        "persist" | "trust" | "untrust" | "transfer" | "password" | "reset" | "delete"
+        | "speaker" => Some(PermissionError::NotOwner),
+        "name" | "limit" | "privacy" | "kick" | "region" | "block" | "unblock" | "bitrate" => {
+            Some(PermissionError::NotTrusted)
+        }
+        _ => None,
+    }
+}
+
+fn premium_feature(
+    command: &str,
+    options: &HashMap<&str, ResolvedValue>,
+    row: &VoiceChannelRow,
+) -> Option<PremiumFeature> {
+    match (command, options.get("kbps")) {
+        ("bitrate", Some(ResolvedValue::Integer(kbps))) if *kbps > MAX_STANDARD_KBPS => {
+            Some(PremiumFeature::HighBitrate)
+        }
+        // Anyone can turn persistence back off.
+        ("persist", _) if !row.is_persistent() => Some(PremiumFeature::Persist),
+        ("region", _) => Some(PremiumFeature::Region),
+        _ => None,
+    }
+}
@@ -1,30 +1,30 @@
 use std::collections::HashMap;
 
 use serenity::all::{
-    ChannelId, CommandInteraction, Context, EditChannel, EditInteractionResponse, ResolvedValue,
+    ChannelId, CommandInteraction, Context, EditChannel, EditInteractionResponse, GuildId,
+    ResolvedValue,
 };
 
-use crate::error::PermissionError;
-use crate::{Error, VoiceChannelRow};
+use crate::name_filter::filter_name;
+use crate::{Error, TempVoiceRow};
 
 pub async fn name(
     ctx: &Context,
     interaction: &CommandInteraction,
     mut options: HashMap<&str, ResolvedValue<'_>>,
+    guild_id: GuildId,
     channel_id: ChannelId,
-    row: &VoiceChannelRow,
+    guild_data: Option<&TempVoiceRow>,
 ) -> Result<(), Error> {
     interaction.defer_ephemeral(ctx).await.unwrap();
 
-    if !row.is_trusted(interaction.user.id) {
-        return Err(Error::MissingPermissions(PermissionError::NotTrusted));
-    }
-
     let name = match options.remove("name") {
         Some(ResolvedValue::String(name)) => name.to_string(),
         _ => format!("{}'s Channel", interaction.user.name),
     };
 
+    let name = filter_name(ctx, guild_data, guild_id, name).await?;
+
     channel_id
         .edit(ctx, EditChannel::new().name(name))
         .await
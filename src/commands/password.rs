@@ -1,13 +1,10 @@
 use std::collections::HashMap;
 
-use serenity::all::{
-    ChannelId, CommandInteraction, Context, EditChannel, EditInteractionResponse, GuildId,
-    PermissionOverwrite, PermissionOverwriteType, Permissions, ResolvedValue,
-};
+use serenity::all::{CommandInteraction, Context, EditInteractionResponse, GuildId, ResolvedValue};
 use sqlx::{Database, Pool};
 
-use crate::error::PermissionError;
-use crate::{Error, Result, VoiceChannelRow, VoiceChannelManager};
+use crate::privacy::apply_privacy;
+use crate::{Result, VoiceChannelManager, VoiceChannelMode, VoiceChannelRow};
 
 pub async fn password<Db: Database, Manager: VoiceChannelManager<Db>>(
     ctx: &Context,
@@ -15,40 +12,21 @@ pub async fn password<Db: Database, Manager: VoiceChannelManager<Db>>(
     pool: &Pool<Db>,
     mut options: HashMap<&str, ResolvedValue<'_>>,
     guild_id: GuildId,
-    channel_id: ChannelId,
     mut row: VoiceChannelRow,
 ) -> Result<()> {
     interaction.defer_ephemeral(ctx).await.unwrap();
 
-    if !row.is_owner(interaction.user.id) {
-        return Err(Error::MissingPermissions(PermissionError::NotOwner));
-    }
-
     let pass = match options.remove("pass") {
         Some(ResolvedValue::String(pass)) => pass,
         _ => unreachable!("Password option is required"),
     };
 
     row.password = Some(pass.to_string());
-    row.save::<Db, Manager>(pool).await?;
 
-    let perms = vec![
-        PermissionOverwrite {
-            allow: Permissions::all(),
-            deny: Permissions::empty(),
-            kind: PermissionOverwriteType::Member(interaction.user.id),
-        },
-        PermissionOverwrite {
-            allow: Permissions::empty(),
-            deny: Permissions::CONNECT,
-            kind: PermissionOverwriteType::Role(guild_id.everyone_role()),
-        },
-    ];
-
-    channel_id
-        .edit(ctx, EditChannel::new().permissions(perms))
-        .await
-        .unwrap();
+    // Only members who know the password can connect, so the channel is
+    // locked like any other.
+    apply_privacy(ctx, guild_id, &mut row, Some(VoiceChannelMode::Locked)).await?;
+    row.save::<Db, Manager>(pool).await?;
 
     interaction
         .edit_response(ctx, EditInteractionResponse::new().content("Password set."))
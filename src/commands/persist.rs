@@ -1,30 +1,37 @@
 use serenity::all::{CommandInteraction, Context, EditInteractionResponse};
 use sqlx::{Database, Pool};
 
-use crate::error::PermissionError;
-use crate::{Error, Result, VoiceChannelManager, VoiceChannelRow};
+use crate::{Error, Result, TempVoiceRow, VoiceChannelManager, VoiceChannelRow};
 
 pub async fn persist<Db: Database, Manager: VoiceChannelManager<Db>>(
     ctx: &Context,
     interaction: &CommandInteraction,
     pool: &Pool<Db>,
     mut row: VoiceChannelRow,
+    guild_data: Option<&TempVoiceRow>,
+    is_moderator: bool,
 ) -> Result<()> {
     interaction.defer_ephemeral(ctx).await.unwrap();
 
-    let member = interaction.member.as_ref().unwrap();
-    let is_moderator = member.permissions.unwrap().manage_channels();
-
-    if row.is_owner(interaction.user.id) && !is_moderator {
-        return Err(Error::MissingPermissions(PermissionError::NotOwner));
-    }
-
-    let persistent_count = Manager::count_persistent_channels(pool, interaction.user.id)
-        .await
-        .unwrap();
+    // Only the owner can get this far without being a moderator, so the
+    // quota is checked against the invoking member's roles.
+    let quota = match (guild_data, interaction.member.as_deref()) {
+        (Some(guild_data), Some(member)) => guild_data.persist_quota(member),
+        _ => Some(1),
+    };
 
-    if persistent_count == 1 && !is_moderator {
-        return Err(Error::MaxChannels);
+    if let Some(quota) = quota
+        && !row.is_persistent()
+        && !is_moderator
+    {
+        let persistent_count =
+            Manager::count_persistent_channels(pool, row.guild_id(), row.owner_id())
+                .await
+                .unwrap();
+
+        if persistent_count >= quota {
+            return Err(Error::MaxChannels(quota));
+        }
     }
 
     row.toggle_persist();
@@ -1,11 +1,11 @@
 use serenity::all::{
     ChannelId, CommandInteraction, Context, EditChannel, EditInteractionResponse, GuildId,
-    PermissionOverwriteType,
 };
 use sqlx::{Database, Pool};
 
-use crate::error::PermissionError;
-use crate::{Error, Result, VoiceChannelManager, VoiceChannelRow};
+use crate::name_filter::sanitise_name;
+use crate::privacy::new_channel_overwrites;
+use crate::{Result, TempVoiceRow, VoiceChannelManager, VoiceChannelRow};
 
 pub async fn reset<Db: Database, Manager: VoiceChannelManager<Db>>(
     ctx: &Context,
@@ -14,39 +14,36 @@ pub async fn reset<Db: Database, Manager: VoiceChannelManager<Db>>(
     guild_id: GuildId,
     channel_id: ChannelId,
     mut row: VoiceChannelRow,
+    guild_data: Option<&TempVoiceRow>,
 ) -> Result<()> {
     interaction.defer_ephemeral(ctx).await.unwrap();
 
-    if !row.is_owner(interaction.user.id) {
-        return Err(Error::MissingPermissions(PermissionError::NotOwner));
-    }
-
     row.reset();
-    row.save::<Db, Manager>(pool).await?;
 
-    let channel = guild_id
-        .channels(ctx)
-        .await
-        .unwrap()
-        .remove(&channel_id)
-        .ok_or(Error::ChannelNotFound(channel_id))?;
+    // Everyone but the owner loses their overwrite, while the mode and
+    // restrictions are kept.
+    let perms = new_channel_overwrites(guild_id, row.owner_id(), row.mode(), &row.restrictions());
 
-    let everyone_permissions = channel
-        .permission_overwrites
-        .iter()
-        .find(|perm| perm.kind == PermissionOverwriteType::Role(guild_id.everyone_role()))
-        .expect("Expected everyone role in channel permissions");
+    row.save::<Db, Manager>(pool).await?;
+
+    let name = sanitise_name(
+        ctx,
+        guild_data,
+        guild_id,
+        format!("{}'s Channel", interaction.user.display_name()),
+        "Voice Channel",
+    )
+    .await;
 
     channel_id
         .edit(
             ctx,
             EditChannel::new()
-                .name(format!("{}'s Channel", interaction.user.display_name()))
+                .name(name)
                 .user_limit(0)
-                .permissions(vec![everyone_permissions.clone()]),
+                .permissions(perms),
         )
-        .await
-        .unwrap();
+        .await?;
 
     interaction
         .edit_response(
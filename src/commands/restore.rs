@@ -0,0 +1,95 @@
+use serenity::all::{CommandInteraction, Context, EditInteractionResponse, GuildId, Mentionable};
+use sqlx::{Database, Pool};
+
+use crate::hibernation;
+use crate::{
+    CreationLimits, Error, Result, TempVoiceBan, TempVoiceGuildManager, VoiceChannelManager,
+};
+
+pub async fn restore<
+    Db: Database,
+    GuildManager: TempVoiceGuildManager<Db>,
+    ChannelManager: VoiceChannelManager<Db>,
+>(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    pool: &Pool<Db>,
+    guild_id: GuildId,
+) -> Result<()> {
+    interaction.defer_ephemeral(ctx).await.unwrap();
+
+    if let Some(ban) =
+        TempVoiceBan::get_active::<Db, GuildManager>(pool, guild_id, interaction.user.id).await?
+    {
+        return Err(Error::Banned(ban.expires_at));
+    }
+
+    let mut guild_data = GuildManager::get(pool, guild_id)
+        .await
+        .map_err(|_| Error::NotSetup)?;
+
+    let rows = ChannelManager::get_by_user(pool, guild_id, interaction.user.id)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|row| row.is_owner(interaction.user.id) && row.is_hibernated())
+        .collect::<Vec<_>>();
+
+    if rows.is_empty() {
+        return Err(Error::NoHibernatedChannels);
+    }
+
+    let mut channels = Vec::with_capacity(rows.len());
+    // Why the remaining channels couldn't be restored, if a limit was hit.
+    let mut stopped = None;
+
+    for row in rows {
+        if let Err(e) = CreationLimits::check::<Db, GuildManager, ChannelManager>(
+            ctx,
+            pool,
+            &guild_data,
+            interaction.user.id,
+        )
+        .await
+        {
+            stopped = Some(e);
+            break;
+        }
+
+        let vc = match hibernation::restore::<Db, GuildManager, ChannelManager>(
+            ctx,
+            pool,
+            &mut guild_data,
+            row,
+            interaction.user.id,
+        )
+        .await
+        {
+            // The broken row is gone, so carry on with the rest.
+            Err(Error::CorruptSnapshot) => continue,
+            r => r?,
+        };
+
+        CreationLimits::record::<Db, GuildManager>(ctx, pool, guild_id, interaction.user.id)
+            .await?;
+
+        channels.push(vc.mention().to_string());
+    }
+
+    if channels.is_empty() {
+        return Err(stopped.unwrap_or(Error::CorruptSnapshot));
+    }
+
+    let mut content = format!("Restored {}.", channels.join(", "));
+
+    if let Some(e) = stopped {
+        content.push_str(&format!("\nThe rest are still hibernated. {e}"));
+    }
+
+    interaction
+        .edit_response(ctx, EditInteractionResponse::new().content(content))
+        .await
+        .unwrap();
+
+    Ok(())
+}
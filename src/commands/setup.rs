@@ -1,12 +1,11 @@
 use std::collections::HashMap;
 
-use serenity::all::{
-    ChannelType, CommandInteraction, Context, CreateChannel, EditInteractionResponse, GuildId,
-    ResolvedValue,
-};
+use serenity::all::{CommandInteraction, Context, EditInteractionResponse, GuildId, ResolvedValue};
 use sqlx::{Database, Pool};
 
-use crate::{guild_manager::TempVoiceGuildManager, Result};
+use super::admin::missing_permission;
+use crate::creator_channel::create_creator_channel;
+use crate::{Error, Result, guild_manager::TempVoiceGuildManager};
 
 pub async fn setup<Db: Database, Manager: TempVoiceGuildManager<Db>>(
     ctx: &Context,
@@ -17,23 +16,10 @@ pub async fn setup<Db: Database, Manager: TempVoiceGuildManager<Db>>(
 ) -> Result<()> {
     interaction.defer_ephemeral(ctx).await.unwrap();
 
-    if !interaction
-        .member
-        .as_ref()
-        .unwrap()
-        .permissions
-        .unwrap()
-        .administrator()
-    {
-        interaction
-            .edit_response(
-                ctx,
-                EditInteractionResponse::new()
-                    .content("You must be an administrator to run this command."),
-            )
-            .await
-            .unwrap();
-        return Ok(());
+    let member = interaction.member.as_deref().ok_or(Error::MissingGuildId)?;
+
+    if let Some(required) = missing_permission(member, None, "setup") {
+        return Err(Error::MissingPermissions(required));
     }
 
     let category = match options.remove("category") {
@@ -41,13 +27,12 @@ pub async fn setup<Db: Database, Manager: TempVoiceGuildManager<Db>>(
         _ => unreachable!("Category is required"),
     };
 
-    let creator_channel = guild_id
-        .create_channel(
-            ctx,
-            CreateChannel::new("➕ Creator Channel")
-                .category(category.id)
-                .kind(ChannelType::Voice),
-        )
+    let log_channel = match options.remove("log_channel") {
+        Some(ResolvedValue::Channel(channel)) => Some(channel.id),
+        _ => None,
+    };
+
+    let creator_channel = create_creator_channel(ctx, guild_id, category.id)
         .await
         .unwrap();
 
@@ -55,6 +40,10 @@ pub async fn setup<Db: Database, Manager: TempVoiceGuildManager<Db>>(
         .await
         .unwrap();
 
+    let mut row = Manager::get(pool, guild_id).await.unwrap();
+    row.set_log_channel(log_channel);
+    row.save::<Db, Manager>(pool).await?;
+
     interaction
         .edit_response(
             ctx,
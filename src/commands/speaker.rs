@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+
+use serenity::all::{
+    ChannelId, CommandInteraction, Context, EditInteractionResponse, ResolvedValue,
+};
+use sqlx::{Database, Pool};
+
+use crate::privacy::grant_speak;
+use crate::{Error, VoiceChannelManager, VoiceChannelMode, VoiceChannelRow};
+
+pub async fn speaker<Db: Database, Manager: VoiceChannelManager<Db>>(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    pool: &Pool<Db>,
+    mut options: HashMap<&str, ResolvedValue<'_>>,
+    channel_id: ChannelId,
+    mut row: VoiceChannelRow,
+) -> Result<(), Error> {
+    interaction.defer_ephemeral(ctx).await.unwrap();
+
+    let user = match options.remove("user") {
+        Some(ResolvedValue::User(user, _member)) => user,
+        _ => unreachable!("User option is required"),
+    };
+
+    if row.mode() != VoiceChannelMode::Spectator {
+        return Err(Error::NotSpectatorMode);
+    }
+
+    row.promote(user.id);
+    row.save::<Db, Manager>(pool).await?;
+
+    grant_speak(ctx, channel_id, user.id).await?;
+
+    interaction
+        .edit_response(
+            ctx,
+            EditInteractionResponse::new().content("Promoted user to speaker."),
+        )
+        .await
+        .unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use std::collections::HashMap;
+
+use serenity::all::{
+    CommandInteraction, Context, CreateEmbed, EditInteractionResponse, GuildId, Mentionable,
+    ResolvedValue,
+};
+use sqlx::{Database, Pool};
+
+use crate::{Result, VoiceStatsManager};
+
+pub async fn stats<Db: Database, StatsManager: VoiceStatsManager<Db>>(
+    ctx: &Context,
+    interaction: &CommandInteraction,
+    pool: &Pool<Db>,
+    mut options: HashMap<&str, ResolvedValue<'_>>,
+    guild_id: GuildId,
+) -> Result<()> {
+    interaction.defer_ephemeral(ctx).await.unwrap();
+
+    let user = match options.remove("user") {
+        Some(ResolvedValue::User(user, _)) => user,
+        _ => &interaction.user,
+    };
+
+    let (sessions, minutes) = match StatsManager::get_user(pool, guild_id, user.id)
+        .await
+        .unwrap()
+    {
+        Some(stats) => (stats.sessions, stats.total_minutes()),
+        None => (0, 0),
+    };
+
+    let embed = CreateEmbed::new()
+        .title("Voice Stats")
+        .description(user.mention().to_string())
+        .field("Time in Voice", format!("{minutes} minutes"), true)
+        .field("Sessions", sessions.to_string(), true);
+
+    interaction
+        .edit_response(ctx, EditInteractionResponse::new().embed(embed))
+        .await
+        .unwrap();
+
+    Ok(())
+}
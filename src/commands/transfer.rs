@@ -1,13 +1,13 @@
 use std::collections::HashMap;
 
 use serenity::all::{
-    ChannelId, CommandInteraction, Context, EditInteractionResponse, PermissionOverwrite,
-    PermissionOverwriteType, Permissions, ResolvedValue,
+    ChannelId, CommandInteraction, Context, EditInteractionResponse, Mentionable,
+    PermissionOverwrite, PermissionOverwriteType, Permissions, ResolvedValue,
 };
 use sqlx::{Database, Pool};
 
-use crate::error::PermissionError;
-use crate::{Error, Result, VoiceChannelManager, VoiceChannelRow};
+use crate::notifier::{Notification, notify};
+use crate::{Result, VoiceChannelManager, VoiceChannelRow};
 
 pub async fn transfer<Db: Database, Manager: VoiceChannelManager<Db>>(
     ctx: &Context,
@@ -19,10 +19,6 @@ pub async fn transfer<Db: Database, Manager: VoiceChannelManager<Db>>(
 ) -> Result<()> {
     interaction.defer_ephemeral(ctx).await.unwrap();
 
-    if !row.is_owner(interaction.user.id) {
-        return Err(Error::MissingPermissions(PermissionError::NotOwner));
-    }
-
     let user = match options.remove("user") {
         Some(ResolvedValue::User(user, _)) => user,
         _ => unreachable!("User option is required"),
@@ -43,6 +39,20 @@ pub async fn transfer<Db: Database, Manager: VoiceChannelManager<Db>>(
         .await
         .unwrap();
 
+    notify(
+        ctx,
+        Notification::new(
+            user.id,
+            format!(
+                "{} transferred ownership of {} to you.",
+                interaction.user.mention(),
+                channel_id.mention()
+            ),
+        )
+        .channel(channel_id),
+    )
+    .await;
+
     interaction
         .edit_response(
             ctx,
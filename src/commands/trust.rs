@@ -7,8 +7,7 @@ use serenity::all::{
 };
 use sqlx::{Database, Pool};
 
-use crate::error::PermissionError;
-use crate::{Error, VoiceChannelRow, VoiceChannelManager};
+use crate::{Error, VoiceChannelManager, VoiceChannelMode, VoiceChannelRow};
 
 pub async fn trust<Db: Database, Manager: VoiceChannelManager<Db>>(
     ctx: &Context,
@@ -20,15 +19,21 @@ pub async fn trust<Db: Database, Manager: VoiceChannelManager<Db>>(
 ) -> Result<(), Error> {
     interaction.defer_ephemeral(ctx).await.unwrap();
 
-    if !row.is_owner(interaction.user.id) {
-        return Err(Error::MissingPermissions(PermissionError::NotOwner));
-    }
-
     let user = match options.remove("user") {
         Some(ResolvedValue::User(user, _member)) => user,
         _ => unreachable!("User option is required"),
     };
 
+    let mut allow = Permissions::VIEW_CHANNEL
+        | Permissions::MANAGE_CHANNELS
+        | Permissions::CONNECT
+        | Permissions::SET_VOICE_CHANNEL_STATUS;
+
+    // Trusted users always speak in spectator mode.
+    if row.mode() == VoiceChannelMode::Spectator {
+        allow |= Permissions::SPEAK;
+    }
+
     row.trust(user.id);
     row.save::<Db, Manager>(pool).await?;
 
@@ -36,10 +41,7 @@ pub async fn trust<Db: Database, Manager: VoiceChannelManager<Db>>(
         .create_permission(
             ctx,
             PermissionOverwrite {
-                allow: Permissions::VIEW_CHANNEL
-                    | Permissions::MANAGE_CHANNELS
-                    | Permissions::CONNECT
-                    | Permissions::SET_VOICE_CHANNEL_STATUS,
+                allow,
                 deny: Permissions::empty(),
                 kind: PermissionOverwriteType::Member(user.id),
             },
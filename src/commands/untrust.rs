@@ -6,8 +6,7 @@ use serenity::all::{
 };
 use sqlx::{Database, Pool};
 
-use crate::error::PermissionError;
-use crate::{Error, VoiceChannelRow, VoiceChannelManager};
+use crate::{Error, VoiceChannelManager, VoiceChannelRow};
 
 pub async fn untrust<Db: Database, Manager: VoiceChannelManager<Db>>(
     ctx: &Context,
@@ -19,10 +18,6 @@ pub async fn untrust<Db: Database, Manager: VoiceChannelManager<Db>>(
 ) -> Result<(), Error> {
     interaction.defer_ephemeral(ctx).await.unwrap();
 
-    if !row.is_owner(interaction.user.id) {
-        return Err(Error::MissingPermissions(PermissionError::NotOwner));
-    }
-
     let user = match options.remove("user") {
         Some(ResolvedValue::User(user, _member)) => user,
         _ => unreachable!("User option is required"),
@@ -0,0 +1,187 @@
+use serenity::all::{
+    ButtonStyle, ComponentInteraction, ComponentInteractionDataKind, Context, CreateActionRow,
+    CreateButton, CreateSelectMenu, CreateSelectMenuKind, EditInteractionResponse,
+};
+use sqlx::{Database, Pool};
+
+use crate::error::PermissionError;
+use crate::metrics::Metrics;
+use crate::privacy::{apply_privacy, grant_speak};
+use crate::{
+    AuditAction, AuditEvent, AuditLog, Error, Result, TempVoiceGuildManager, VoiceChannelManager,
+    VoiceChannelMode,
+};
+
+const CUSTOM_ID_PREFIX: &str = "temp_voice_panel_";
+const SPEAKER: &str = "speaker";
+
+/// Buttons posted in a channel's text chat so its owner can manage it without
+/// typing commands.
+pub struct ControlPanel;
+
+impl ControlPanel {
+    /// Handles a control panel button press. Components from elsewhere are
+    /// ignored, so every component interaction can be passed through here.
+    pub async fn run<
+        Db: Database,
+        GuildManager: TempVoiceGuildManager<Db>,
+        ChannelManager: VoiceChannelManager<Db>,
+    >(
+        ctx: &Context,
+        interaction: &ComponentInteraction,
+        pool: &Pool<Db>,
+    ) -> Result<()> {
+        let result = Self::press::<Db, GuildManager, ChannelManager>(ctx, interaction, pool).await;
+
+        Metrics::track(ctx, result).await
+    }
+
+    async fn press<
+        Db: Database,
+        GuildManager: TempVoiceGuildManager<Db>,
+        ChannelManager: VoiceChannelManager<Db>,
+    >(
+        ctx: &Context,
+        interaction: &ComponentInteraction,
+        pool: &Pool<Db>,
+    ) -> Result<()> {
+        let Some(action) = interaction.data.custom_id.strip_prefix(CUSTOM_ID_PREFIX) else {
+            return Ok(());
+        };
+
+        Metrics::get(ctx).await.command(&format!("panel {action}"));
+
+        let guild_id = interaction.guild_id.ok_or(Error::MissingGuildId)?;
+
+        interaction.defer_ephemeral(ctx).await.unwrap();
+
+        // The panel lives in the voice channel's own text chat.
+        let channel_id = interaction.channel_id;
+
+        let mut row = ChannelManager::get(pool, channel_id)
+            .await
+            .unwrap()
+            .ok_or(Error::ChannelNotFound(channel_id))?;
+
+        let is_moderator = match (
+            interaction.member.as_ref(),
+            GuildManager::get(pool, guild_id).await.ok(),
+        ) {
+            (Some(member), Some(guild_data)) => guild_data.is_moderator(member),
+            _ => false,
+        };
+
+        let (required, audit_action) = match action {
+            SPEAKER => (PermissionError::NotOwner, AuditAction::Speaker),
+            _ => (PermissionError::NotTrusted, AuditAction::Privacy),
+        };
+
+        let mut event = AuditEvent::new(guild_id, channel_id, interaction.user.id, audit_action);
+
+        let permitted = match required {
+            PermissionError::NotOwner => row.is_owner(interaction.user.id),
+            _ => row.is_trusted(interaction.user.id),
+        };
+
+        if !permitted {
+            if !is_moderator {
+                return Err(Error::MissingPermissions(required));
+            }
+
+            event.moderator_override = true;
+        }
+
+        let content = match (action, &interaction.data.kind) {
+            (SPEAKER, ComponentInteractionDataKind::UserSelect { values }) => {
+                if row.mode() != VoiceChannelMode::Spectator {
+                    return Err(Error::NotSpectatorMode);
+                }
+
+                for user_id in values {
+                    row.promote(*user_id);
+                }
+                row.save::<Db, ChannelManager>(pool).await?;
+
+                for user_id in values {
+                    grant_speak(ctx, channel_id, *user_id).await?;
+                }
+
+                event.target_id = values.first().copied();
+
+                "Promoted to speaker."
+            }
+            (SPEAKER, _) => {
+                interaction
+                    .edit_response(
+                        ctx,
+                        EditInteractionResponse::new().content("No users selected."),
+                    )
+                    .await
+                    .unwrap();
+
+                return Ok(());
+            }
+            (privacy, _) => {
+                let mode = VoiceChannelMode::from_name(privacy).ok_or(Error::InvalidPrivacy)?;
+                apply_privacy(ctx, guild_id, &mut row, Some(mode)).await?;
+                row.save::<Db, ChannelManager>(pool).await?;
+
+                "Channel privacy updated."
+            }
+        };
+
+        interaction
+            .edit_response(ctx, EditInteractionResponse::new().content(content))
+            .await
+            .unwrap();
+
+        AuditLog::record::<Db, GuildManager>(ctx, pool, event).await;
+
+        Ok(())
+    }
+
+    pub fn components() -> Vec<CreateActionRow> {
+        vec![
+            CreateActionRow::Buttons(vec![
+                CreateButton::new(format!(
+                    "{CUSTOM_ID_PREFIX}{}",
+                    VoiceChannelMode::Open.as_str()
+                ))
+                .label("Open")
+                .emoji('🔓')
+                .style(ButtonStyle::Secondary),
+                CreateButton::new(format!(
+                    "{CUSTOM_ID_PREFIX}{}",
+                    VoiceChannelMode::Spectator.as_str()
+                ))
+                .label("Spectate")
+                .emoji('🎧')
+                .style(ButtonStyle::Secondary),
+                CreateButton::new(format!(
+                    "{CUSTOM_ID_PREFIX}{}",
+                    VoiceChannelMode::Locked.as_str()
+                ))
+                .label("Lock")
+                .emoji('🔒')
+                .style(ButtonStyle::Secondary),
+                CreateButton::new(format!(
+                    "{CUSTOM_ID_PREFIX}{}",
+                    VoiceChannelMode::Invisible.as_str()
+                ))
+                .label("Hide")
+                .emoji('👻')
+                .style(ButtonStyle::Secondary),
+            ]),
+            CreateActionRow::SelectMenu(
+                CreateSelectMenu::new(
+                    format!("{CUSTOM_ID_PREFIX}{SPEAKER}"),
+                    CreateSelectMenuKind::User {
+                        default_users: None,
+                    },
+                )
+                .placeholder("Promote spectators to speakers")
+                .max_values(10),
+            ),
+        ]
+    }
+}
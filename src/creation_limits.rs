@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serenity::all::{Context, GuildId, Timestamp, UserId};
+use serenity::prelude::TypeMapKey;
+use sqlx::{Database, Pool};
+
+use crate::{Error, Result, TempVoiceGuildManager, TempVoiceRow, VoiceChannelManager};
+
+/// Tracks when each user last created a channel so the creation cooldown
+/// doesn't need a database round trip on every join. The guild manager is the
+/// source of truth after a restart.
+#[derive(Default)]
+pub struct CreationLimits {
+    last_created: Mutex<HashMap<(GuildId, UserId), i64>>,
+}
+
+impl CreationLimits {
+    async fn get(ctx: &Context) -> Arc<Self> {
+        if let Some(limits) = ctx.data.read().await.get::<Self>() {
+            return Arc::clone(limits);
+        }
+
+        let mut data = ctx.data.write().await;
+        Arc::clone(data.entry::<Self>().or_default())
+    }
+
+    pub async fn check<
+        Db: Database,
+        GuildManager: TempVoiceGuildManager<Db>,
+        ChannelManager: VoiceChannelManager<Db>,
+    >(
+        ctx: &Context,
+        pool: &Pool<Db>,
+        guild_data: &TempVoiceRow,
+        user_id: UserId,
+    ) -> Result<()> {
+        let guild_id = guild_data.guild_id();
+
+        if let Some(cooldown) = guild_data.creation_cooldown() {
+            let cached = Self::get(ctx).await.last_created(guild_id, user_id);
+
+            let last_created = match cached {
+                Some(last_created) => Some(last_created),
+                None => GuildManager::get_last_creation(pool, guild_id, user_id)
+                    .await
+                    .unwrap(),
+            };
+
+            if let Some(available_at) =
+                cooldown_ends(last_created, cooldown, Timestamp::now().unix_timestamp())
+            {
+                return Err(Error::CreationCooldown(available_at));
+            }
+        }
+
+        if let Some(limit) = guild_data.max_owned_channels() {
+            let owned = ChannelManager::count_owned_channels(pool, guild_id, user_id)
+                .await
+                .unwrap();
+
+            if owned >= limit {
+                return Err(Error::MaxOwnedChannels(limit));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn record<Db: Database, GuildManager: TempVoiceGuildManager<Db>>(
+        ctx: &Context,
+        pool: &Pool<Db>,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<()> {
+        let now = Timestamp::now().unix_timestamp();
+
+        Self::get(ctx).await.remember(guild_id, user_id, now);
+
+        GuildManager::save_last_creation(pool, guild_id, user_id, now)
+            .await
+            .unwrap();
+
+        Ok(())
+    }
+
+    fn last_created(&self, guild_id: GuildId, user_id: UserId) -> Option<i64> {
+        self.last_created
+            .lock()
+            .unwrap()
+            .get(&(guild_id, user_id))
+            .copied()
+    }
+
+    fn remember(&self, guild_id: GuildId, user_id: UserId, created_at: i64) {
+        self.last_created
+            .lock()
+            .unwrap()
+            .insert((guild_id, user_id), created_at);
+    }
+}
+
+impl TypeMapKey for CreationLimits {
+    type Value = Arc<CreationLimits>;
+}
+
+/// When the user can next create a channel, if they're still cooling down.
+fn cooldown_ends(last_created: Option<i64>, cooldown: i64, now: i64) -> Option<i64> {
+    last_created
+        .map(|last_created| last_created.saturating_add(cooldown))
+        .filter(|available_at| *available_at > now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remembers_creations_per_guild_and_user() {
+        let limits = CreationLimits::default();
+        let user = UserId::new(1);
+
+        limits.remember(GuildId::new(1), user, 100);
+
+        assert_eq!(limits.last_created(GuildId::new(1), user), Some(100));
+        assert_eq!(limits.last_created(GuildId::new(2), user), None);
+        assert_eq!(limits.last_created(GuildId::new(1), UserId::new(2)), None);
+
+        limits.remember(GuildId::new(1), user, 200);
+
+        assert_eq!(limits.last_created(GuildId::new(1), user), Some(200));
+    }
+
+    #[test]
+    fn cooldown_ends_after_the_cooldown() {
+        assert_eq!(cooldown_ends(Some(100), 60, 130), Some(160));
+        assert_eq!(cooldown_ends(Some(100), 60, 160), None);
+        assert_eq!(cooldown_ends(Some(100), 60, 200), None);
+    }
+
+    #[test]
+    fn huge_cooldowns_never_end() {
+        assert_eq!(cooldown_ends(Some(100), i64::MAX, 200), Some(i64::MAX));
+    }
+
+    #[test]
+    fn no_cooldown_without_a_previous_creation() {
+        assert_eq!(cooldown_ends(None, 60, 0), None);
+    }
+}
@@ -0,0 +1,52 @@
+use serenity::all::{
+    ChannelId, ChannelType, Context, CreateChannel, CreateMessage, GuildChannel, GuildId,
+    Mentionable,
+};
+use sqlx::{Database, Pool};
+
+use crate::{Result, TempVoiceGuildManager, TempVoiceRow};
+
+pub(crate) async fn create_creator_channel(
+    ctx: &Context,
+    guild_id: GuildId,
+    category: ChannelId,
+) -> serenity::Result<GuildChannel> {
+    guild_id
+        .create_channel(
+            ctx,
+            CreateChannel::new("➕ Creator Channel")
+                .category(category)
+                .kind(ChannelType::Voice),
+        )
+        .await
+}
+
+/// Recreates a creator channel that was deleted out from under the bot and
+/// points the guild configuration at the new channel.
+pub(crate) async fn restore_creator_channel<Db: Database, Manager: TempVoiceGuildManager<Db>>(
+    ctx: &Context,
+    pool: &Pool<Db>,
+    mut guild_data: TempVoiceRow,
+) -> Result<()> {
+    let guild_id = guild_data.guild_id();
+    let log_channel = guild_data.log_channel();
+
+    let creator_channel = create_creator_channel(ctx, guild_id, guild_data.category()).await?;
+
+    guild_data.set_creator_channel(creator_channel.id);
+    guild_data.save::<Db, Manager>(pool).await?;
+
+    if let Some(log_channel) = log_channel {
+        log_channel
+            .send_message(
+                ctx,
+                CreateMessage::new().content(format!(
+                    "The temp voice creator channel was missing and has been recreated as {}.",
+                    creator_channel.id.mention()
+                )),
+            )
+            .await?;
+    }
+
+    Ok(())
+}
@@ -1,31 +1,84 @@
 use serenity::all::{ChannelId, DiscordJsonError, ErrorResponse, HttpError, Mentionable};
 
+use crate::commands::MAX_STANDARD_KBPS;
+use crate::guild_manager::PremiumFeature;
+
 pub(crate) type Result<T> = std::result::Result<T, Error>;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
+#[allow(clippy::enum_variant_names)]
 pub enum PermissionError {
     NotOwner,
     NotTrusted,
+    NotModerator,
+    NotAdministrator,
 }
 
 #[derive(Debug)]
 pub enum Error {
     MissingGuildId,
+    NotSetup,
     MemberNotInVoiceChannel,
     OwnerInChannel,
     InvalidPassword,
     UserIsOwner,
-    MaxChannels,
+    MaxChannels(i64),
+    Banned(Option<i64>),
+    CreationCooldown(i64),
+    MaxOwnedChannels(i64),
+    InvalidChannelName,
+    CategoryFull,
+    DurationTooLong,
+    NoHibernatedChannels,
+    CorruptSnapshot,
+    NotSpectatorMode,
+    InvalidPrivacy,
+    InvalidNamePattern(regex::Error),
     MissingPermissions(PermissionError),
+    PremiumFeature(PremiumFeature),
     ChannelNotFound(ChannelId),
 
     Serenity(serenity::Error),
 }
 
+impl Error {
+    /// The variant's name, for labelling metrics.
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            Error::MissingGuildId => "MissingGuildId",
+            Error::NotSetup => "NotSetup",
+            Error::MemberNotInVoiceChannel => "MemberNotInVoiceChannel",
+            Error::OwnerInChannel => "OwnerInChannel",
+            Error::InvalidPassword => "InvalidPassword",
+            Error::UserIsOwner => "UserIsOwner",
+            Error::MaxChannels(_) => "MaxChannels",
+            Error::Banned(_) => "Banned",
+            Error::CreationCooldown(_) => "CreationCooldown",
+            Error::MaxOwnedChannels(_) => "MaxOwnedChannels",
+            Error::InvalidChannelName => "InvalidChannelName",
+            Error::CategoryFull => "CategoryFull",
+            Error::DurationTooLong => "DurationTooLong",
+            Error::NoHibernatedChannels => "NoHibernatedChannels",
+            Error::CorruptSnapshot => "CorruptSnapshot",
+            Error::NotSpectatorMode => "NotSpectatorMode",
+            Error::InvalidPrivacy => "InvalidPrivacy",
+            Error::InvalidNamePattern(_) => "InvalidNamePattern",
+            Error::MissingPermissions(_) => "MissingPermissions",
+            Error::PremiumFeature(_) => "PremiumFeature",
+            Error::ChannelNotFound(_) => "ChannelNotFound",
+            Error::Serenity(_) => "Serenity",
+        }
+    }
+}
+
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             Error::MissingGuildId => zayden_core::Error::MissingGuildId.fmt(f),
+            Error::NotSetup => write!(
+                f,
+                "Temporary voice channels haven't been set up. An administrator can run `/voice setup`."
+            ),
             Error::MemberNotInVoiceChannel => {
                 write!(
                     f,
@@ -40,16 +93,72 @@ impl std::fmt::Display for Error {
             }
             Error::InvalidPassword => write!(f, "Invalid channel password."),
             Error::UserIsOwner => write!(f, "You are already the owner of this channel."),
-            Error::MaxChannels => write!(
+            Error::MaxChannels(quota) => write!(
+                f,
+                "You have reached your limit of {quota} persistent channel(s)."
+            ),
+            Error::Banned(None) => write!(
+                f,
+                "You are banned from creating temporary voice channels in this server."
+            ),
+            Error::Banned(Some(expires_at)) => write!(
+                f,
+                "You are banned from creating temporary voice channels in this server until <t:{expires_at}:f>."
+            ),
+            Error::CreationCooldown(available_at) => write!(
+                f,
+                "You're creating channels too quickly. You can create another <t:{available_at}:R>."
+            ),
+            Error::MaxOwnedChannels(limit) => write!(
+                f,
+                "You can only own {limit} temporary voice channel(s) at once. Delete one before creating another."
+            ),
+            Error::InvalidChannelName => {
+                write!(f, "That channel name isn't allowed in this server.")
+            }
+            Error::DurationTooLong => write!(f, "That duration is too long."),
+            Error::CategoryFull => write!(
+                f,
+                "All temporary voice categories are full. Please try again later."
+            ),
+            Error::NoHibernatedChannels => {
+                write!(f, "You don't have any hibernated voice channels.")
+            }
+            Error::CorruptSnapshot => write!(
+                f,
+                "Your hibernated voice channel's settings couldn't be read, so it was removed."
+            ),
+            Error::InvalidPrivacy => write!(
                 f,
-                "You have reached the maximum number of persistent channels."
+                "That privacy option isn't available anymore. Please pick another."
             ),
+            Error::NotSpectatorMode => {
+                write!(f, "This voice channel isn't in spectator mode.")
+            }
+            Error::InvalidNamePattern(e) => write!(f, "Invalid name filter pattern:\n```{e}```"),
             Error::MissingPermissions(PermissionError::NotOwner) => {
                 write!(f, "Only the channel owner can use this command.")
             }
             Error::MissingPermissions(PermissionError::NotTrusted) => {
                 write!(f, "You must be trusted to use this command.")
             }
+            Error::MissingPermissions(PermissionError::NotModerator) => {
+                write!(f, "You must be a moderator to use this command.")
+            }
+            Error::MissingPermissions(PermissionError::NotAdministrator) => {
+                write!(f, "You must be an administrator to use this command.")
+            }
+            Error::PremiumFeature(feature) => {
+                let action = match feature {
+                    PremiumFeature::HighBitrate => {
+                        format!("set a bitrate above {MAX_STANDARD_KBPS} kbps")
+                    }
+                    PremiumFeature::Persist => String::from("persist channels"),
+                    PremiumFeature::Region => String::from("change the channel region"),
+                };
+
+                write!(f, "Only premium members can {action} in this server.")
+            }
             Error::ChannelNotFound(id) => write!(
                 f,
                 "Channel not found: {}\nTry using `/voice claim` to claim the channel.",
@@ -72,9 +181,10 @@ impl std::fmt::Display for Error {
                     "I'm missing permissions perform that action. Please contact a server admin to resolve this."
                 )
             }
-            Self::Serenity(e) => {
-                unimplemented!("Unhandled serenity error: {e:?}")
-            }
+            Self::Serenity(_) => write!(
+                f,
+                "Something went wrong while talking to Discord. Please try again."
+            ),
         }
     }
 }
@@ -86,3 +196,19 @@ impl From<serenity::Error> for Error {
         Self::Serenity(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unhandled_serenity_errors_still_display() {
+        let error = Error::from(serenity::Error::Other("gateway closed"));
+
+        assert_eq!(
+            error.to_string(),
+            "Something went wrong while talking to Discord. Please try again."
+        );
+        assert_eq!(error.variant_name(), "Serenity");
+    }
+}
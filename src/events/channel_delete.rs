@@ -0,0 +1,80 @@
+use serenity::all::{Context, GuildChannel};
+use sqlx::{Database, Pool};
+
+use crate::category::cleanup_category;
+use crate::creator_channel::restore_creator_channel;
+use crate::metrics::Metrics;
+use crate::{Result, TempVoiceGuildManager, VoiceChannelManager, VoiceStatsManager};
+
+pub async fn channel_delete<
+    Db: Database,
+    GuildManager: TempVoiceGuildManager<Db>,
+    ChannelManager: VoiceChannelManager<Db>,
+    StatsManager: VoiceStatsManager<Db>,
+>(
+    ctx: &Context,
+    pool: &Pool<Db>,
+    channel: &GuildChannel,
+) -> Result<()> {
+    let result =
+        clean_up::<Db, GuildManager, ChannelManager, StatsManager>(ctx, pool, channel).await;
+
+    Metrics::track(ctx, result).await
+}
+
+async fn clean_up<
+    Db: Database,
+    GuildManager: TempVoiceGuildManager<Db>,
+    ChannelManager: VoiceChannelManager<Db>,
+    StatsManager: VoiceStatsManager<Db>,
+>(
+    ctx: &Context,
+    pool: &Pool<Db>,
+    channel: &GuildChannel,
+) -> Result<()> {
+    if let Some(mut session) = StatsManager::get_session(pool, channel.id).await.unwrap() {
+        session.end();
+        session.save::<Db, StatsManager>(pool).await?;
+    }
+
+    let row = ChannelManager::get(pool, channel.id).await.unwrap();
+    let is_temp = row.is_some();
+
+    // Hibernated channels keep their row until they are restored.
+    if let Some(row) = row
+        && !row.is_hibernated()
+    {
+        row.delete::<Db, ChannelManager>(pool).await?;
+        Metrics::channel_deleted::<Db, ChannelManager>(ctx, pool, channel.guild_id).await;
+    }
+
+    let Ok(mut guild_data) = GuildManager::get(pool, channel.guild_id).await else {
+        return Ok(());
+    };
+
+    // The row may already be gone if the bot deleted the channel itself, so
+    // any channel leaving an overflow category is enough to check it.
+    if let Some(category) = channel.parent_id
+        && guild_data.is_auto_category(category)
+    {
+        return cleanup_category::<Db, GuildManager>(ctx, pool, guild_data, category, channel.id)
+            .await;
+    }
+
+    if is_temp {
+        return Ok(());
+    }
+
+    // Without its category the module can't work, so drop the configuration
+    // and let an admin run `/voice setup` again.
+    if guild_data.temp_voice_category == Some(channel.id.get() as i64) {
+        GuildManager::delete(pool, channel.guild_id).await.unwrap();
+    } else if guild_data.temp_voice_creator_channel == Some(channel.id.get() as i64) {
+        restore_creator_channel::<Db, GuildManager>(ctx, pool, guild_data).await?;
+    } else if guild_data.is_temp_category(channel.id) {
+        guild_data.remove_category(channel.id);
+        guild_data.save::<Db, GuildManager>(pool).await?;
+    }
+
+    Ok(())
+}
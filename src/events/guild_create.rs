@@ -1,29 +1,37 @@
 use serenity::all::{Context, Guild};
+use sqlx::{Database, Pool};
 
-use crate::{CachedState, VoiceStateCache};
-
-pub async fn guild_create(ctx: &Context, guild: &Guild) {
-    let mut data = ctx.data.write().await;
-
-    for (user_id, state) in guild
-        .voice_states
-        .iter()
-        .filter(|(_, state)| state.channel_id.is_some())
-    {
-        data.entry::<VoiceStateCache>()
-            .and_modify(|cache| {
-                cache.insert(
-                    *user_id,
-                    CachedState::new(state.channel_id, guild.id, state.user_id),
-                );
-            })
-            .or_insert_with(|| {
-                [(
-                    *user_id,
-                    CachedState::new(state.channel_id, guild.id, state.user_id),
-                )]
-                .into_iter()
-                .collect()
-            });
+use crate::creator_channel::restore_creator_channel;
+use crate::{Result, TempVoiceGuildManager, VoiceStateCache};
+
+pub async fn guild_create<Db: Database, GuildManager: TempVoiceGuildManager<Db>>(
+    ctx: &Context,
+    pool: &Pool<Db>,
+    guild: &Guild,
+) -> Result<()> {
+    let cache = {
+        let mut data = ctx.data.write().await;
+        data.entry::<VoiceStateCache>().or_default().clone()
+    };
+
+    cache.insert_guild(guild);
+
+    let Ok(guild_data) = GuildManager::get(pool, guild.id).await else {
+        return Ok(());
+    };
+
+    if guild_data.temp_voice_category.is_none() || guild_data.temp_voice_creator_channel.is_none() {
+        return Ok(());
     }
+
+    if !guild.channels.contains_key(&guild_data.category()) {
+        GuildManager::delete(pool, guild.id).await.unwrap();
+        return Ok(());
+    }
+
+    if !guild.channels.contains_key(&guild_data.creator_channel()) {
+        restore_creator_channel::<Db, GuildManager>(ctx, pool, guild_data).await?;
+    }
+
+    Ok(())
 }
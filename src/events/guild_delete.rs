@@ -0,0 +1,37 @@
+use serenity::all::{Context, UnavailableGuild};
+use sqlx::{Database, Pool};
+
+use crate::{
+    Result, TempVoiceGuildManager, VoiceChannelManager, VoiceStateCache, VoiceStatsManager,
+};
+
+pub async fn guild_delete<
+    Db: Database,
+    GuildManager: TempVoiceGuildManager<Db>,
+    ChannelManager: VoiceChannelManager<Db>,
+    StatsManager: VoiceStatsManager<Db>,
+>(
+    ctx: &Context,
+    pool: &Pool<Db>,
+    incomplete: &UnavailableGuild,
+    purge_data: bool,
+) -> Result<()> {
+    // The guild is only unavailable due to an outage, the bot wasn't removed.
+    if incomplete.unavailable {
+        return Ok(());
+    }
+
+    VoiceStateCache::get(ctx).await.remove_guild(incomplete.id);
+
+    if purge_data {
+        ChannelManager::delete_guild(pool, incomplete.id)
+            .await
+            .unwrap();
+        GuildManager::delete(pool, incomplete.id).await.unwrap();
+        StatsManager::delete_guild(pool, incomplete.id)
+            .await
+            .unwrap();
+    }
+
+    Ok(())
+}
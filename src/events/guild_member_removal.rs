@@ -0,0 +1,69 @@
+use serenity::all::{Context, DiscordJsonError, ErrorResponse, GuildId, HttpError, User};
+use sqlx::{Database, Pool};
+use tracing::warn;
+
+use crate::metrics::{Metrics, timed};
+use crate::{Result, VoiceChannelManager, VoiceStateCache};
+
+pub async fn guild_member_removal<Db: Database, ChannelManager: VoiceChannelManager<Db>>(
+    ctx: &Context,
+    pool: &Pool<Db>,
+    guild_id: GuildId,
+    user: &User,
+) -> Result<()> {
+    let result = remove_member::<Db, ChannelManager>(ctx, pool, guild_id, user).await;
+
+    Metrics::track(ctx, result).await
+}
+
+async fn remove_member<Db: Database, ChannelManager: VoiceChannelManager<Db>>(
+    ctx: &Context,
+    pool: &Pool<Db>,
+    guild_id: GuildId,
+    user: &User,
+) -> Result<()> {
+    let cache = VoiceStateCache::get(ctx).await;
+    cache.remove_user(guild_id, user.id);
+
+    let rows = ChannelManager::get_by_user(pool, guild_id, user.id)
+        .await
+        .unwrap();
+
+    for mut row in rows {
+        if !row.is_owner(user.id) {
+            row.remove_user(user.id);
+            row.save::<Db, ChannelManager>(pool).await?;
+            continue;
+        }
+
+        // Remaining members can claim the channel, and it will be cleaned up
+        // as usual once it empties.
+        if row.is_persistent() {
+            row.toggle_persist();
+        }
+
+        if cache.member_count(guild_id, row.channel_id()) > 0 {
+            row.save::<Db, ChannelManager>(pool).await?;
+            continue;
+        }
+
+        let channel_id = row.channel_id();
+        row.delete::<Db, ChannelManager>(pool).await?;
+
+        match timed(ctx, "delete_channel", channel_id.delete(ctx)).await {
+            Err(serenity::Error::Http(HttpError::UnsuccessfulRequest(ErrorResponse {
+                error: DiscordJsonError { code: 10003, .. },
+                ..
+            }))) => {
+                warn!(%channel_id, "channel already deleted");
+            }
+            result => {
+                result?;
+            }
+        };
+
+        Metrics::channel_deleted::<Db, ChannelManager>(ctx, pool, guild_id).await;
+    }
+
+    Ok(())
+}
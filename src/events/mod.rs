@@ -1,4 +1,11 @@
+pub mod channel_delete;
 pub mod guild_create;
+pub mod guild_delete;
+pub mod guild_member_removal;
 pub mod voice_state_update;
 
+pub use channel_delete::channel_delete;
 pub use guild_create::guild_create;
+pub use guild_delete::guild_delete;
+pub use guild_member_removal::guild_member_removal;
+pub use voice_state_update::voice_state_update;
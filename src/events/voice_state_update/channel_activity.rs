@@ -0,0 +1,33 @@
+use serenity::all::{ChannelId, VoiceState};
+use sqlx::{Database, Pool};
+
+use crate::{CachedState, Result, VoiceChannelManager};
+
+/// Refreshes the last activity of persistent channels whenever someone joins
+/// or leaves them, which idle expiry is measured from.
+pub(super) async fn channel_activity<Db: Database, ChannelManager: VoiceChannelManager<Db>>(
+    pool: &Pool<Db>,
+    old: Option<&CachedState>,
+    new: &VoiceState,
+) -> Result<()> {
+    let channels = [old.and_then(|old| old.channel_id), new.channel_id];
+
+    for channel_id in channels.into_iter().flatten() {
+        touch::<Db, ChannelManager>(pool, channel_id).await?;
+    }
+
+    Ok(())
+}
+
+async fn touch<Db: Database, ChannelManager: VoiceChannelManager<Db>>(
+    pool: &Pool<Db>,
+    channel_id: ChannelId,
+) -> Result<()> {
+    let mut row = match ChannelManager::get(pool, channel_id).await.unwrap() {
+        Some(row) if row.is_persistent() => row,
+        _ => return Ok(()),
+    };
+
+    row.touch();
+    row.save::<Db, ChannelManager>(pool).await
+}
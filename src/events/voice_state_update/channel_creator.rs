@@ -1,15 +1,24 @@
 use serenity::all::{
-    ChannelType, Context, CreateChannel, CreateMessage, DiscordJsonError, ErrorResponse, HttpError,
-    PermissionOverwrite, PermissionOverwriteType, Permissions, VoiceState,
+    ChannelType, Context, CreateChannel, DiscordJsonError, ErrorResponse, GuildId, HttpError,
+    Member, VoiceState,
 };
 use sqlx::{Database, Pool};
+use tracing::warn;
 
+use crate::category::available_category;
+use crate::hibernation::restore;
+use crate::metrics::{Metrics, timed};
+use crate::name_filter::sanitise_name;
+use crate::notifier::{Notification, notify};
+use crate::ordering::reorder_category;
+use crate::privacy::new_channel_overwrites;
+use crate::welcome::send_welcome;
 use crate::{
-    Result, TempVoiceGuildManager, VoiceChannelManager, VoiceChannelRow,
-    delete_voice_channel_if_inactive,
+    AuditAction, AuditEvent, AuditLog, CreationLimits, Error, Result, TempVoiceBan,
+    TempVoiceGuildManager, VoiceChannelManager, VoiceChannelRow, spawn_grace_period,
 };
 
-pub async fn channel_creator<
+pub(super) async fn channel_creator<
     Db: Database,
     GuildManager: TempVoiceGuildManager<Db>,
     ChannelManager: VoiceChannelManager<Db>,
@@ -22,73 +31,193 @@ pub async fn channel_creator<
         .guild_id
         .expect("Should be in a guild as voice channels are guild only");
 
-    let Ok(Some(creator_channel)) = GuildManager::get_creator_channel(pool, guild_id).await else {
+    let Ok(mut guild_data) = GuildManager::get(pool, guild_id).await else {
         return Ok(());
     };
 
-    let creator_channel_id = match new.channel_id {
-        Some(channel) if channel == creator_channel => channel,
+    match (new.channel_id, guild_data.temp_voice_creator_channel) {
+        (Some(channel), Some(creator_channel)) if channel.get() as i64 == creator_channel => {}
         _ => return Ok(()),
     };
 
-    let creator_category = creator_channel_id
-        .to_channel(ctx)
+    let member = new.member.as_ref().expect("Should be in a guild");
+
+    if let Some(ban) =
+        TempVoiceBan::get_active::<Db, GuildManager>(pool, guild_id, member.user.id).await?
+    {
+        reject(ctx, guild_id, member, Error::Banned(ban.expires_at)).await?;
+        return Ok(());
+    }
+
+    if let Err(e) = CreationLimits::check::<Db, GuildManager, ChannelManager>(
+        ctx,
+        pool,
+        &guild_data,
+        member.user.id,
+    )
+    .await
+    {
+        reject(ctx, guild_id, member, e).await?;
+        return Ok(());
+    }
+
+    // Owners get their hibernated channel back instead of a new one.
+    let hibernated = ChannelManager::get_by_user(pool, guild_id, member.user.id)
         .await
         .unwrap()
-        .guild()
-        .expect("Should be in a guild")
-        .parent_id
-        .expect("Should be in a category");
+        .into_iter()
+        .find(|row| row.is_owner(member.user.id) && row.is_hibernated());
 
-    let member = new.member.as_ref().expect("Should be in a guild");
+    if let Some(row) = hibernated {
+        match restore::<Db, GuildManager, ChannelManager>(
+            ctx,
+            pool,
+            &mut guild_data,
+            row,
+            member.user.id,
+        )
+        .await
+        {
+            Ok(vc) => {
+                CreationLimits::record::<Db, GuildManager>(ctx, pool, guild_id, member.user.id)
+                    .await?;
+
+                match timed(
+                    ctx,
+                    "move_member",
+                    guild_id.move_member(ctx, member.user.id, vc.id),
+                )
+                .await
+                {
+                    // Target user is not connected to voice.
+                    Err(serenity::Error::Http(HttpError::UnsuccessfulRequest(ErrorResponse {
+                        error: DiscordJsonError { code: 40032, .. },
+                        ..
+                    }))) => {}
+                    result => {
+                        result?;
+                    }
+                };
+
+                return Ok(());
+            }
+            Err(Error::CategoryFull) => {
+                reject(ctx, guild_id, member, Error::CategoryFull).await?;
+                return Ok(());
+            }
+            // The unreadable row was dropped, so they get a fresh channel.
+            Err(Error::CorruptSnapshot) => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    let category = match available_category::<Db, GuildManager>(ctx, pool, &mut guild_data).await {
+        Err(Error::CategoryFull) => {
+            reject(ctx, guild_id, member, Error::CategoryFull).await?;
+            return Ok(());
+        }
+        r => r?,
+    };
+
+    let mode = guild_data.default_mode();
+    let restrictions = guild_data.default_restrictions();
+    let perms = new_channel_overwrites(guild_id, member.user.id, mode, &restrictions);
 
-    let perms = vec![PermissionOverwrite {
-        allow: Permissions::all(),
-        deny: Permissions::empty(),
-        kind: PermissionOverwriteType::Member(member.user.id),
-    }];
+    let name = sanitise_name(
+        ctx,
+        Some(&guild_data),
+        guild_id,
+        format!("{}'s Channel", member.display_name()),
+        "Voice Channel",
+    )
+    .await;
 
-    let vc_builder = CreateChannel::new(format!("{}'s Channel", member.display_name()))
+    let vc_builder = CreateChannel::new(name)
         .kind(ChannelType::Voice)
-        .category(creator_category)
+        .category(category)
         .permissions(perms);
 
-    let vc = match guild_id.create_channel(ctx, vc_builder).await {
+    let vc = match timed(
+        ctx,
+        "create_channel",
+        guild_id.create_channel(ctx, vc_builder),
+    )
+    .await
+    {
         // Missing Permission
         Err(serenity::Error::Http(HttpError::UnsuccessfulRequest(ErrorResponse {
             error: DiscordJsonError { code: 50013, .. },
             ..
-        }))) => return Ok(()),
+        }))) => {
+            warn!(%category, "missing permissions to create channel");
+            return Ok(());
+        }
         r => r?,
     };
 
-    match guild_id.move_member(ctx, member.user.id, vc.id).await {
+    CreationLimits::record::<Db, GuildManager>(ctx, pool, guild_id, member.user.id).await?;
+
+    let grace_period = match timed(
+        ctx,
+        "move_member",
+        guild_id.move_member(ctx, member.user.id, vc.id),
+    )
+    .await
+    {
         // Target user is not connected to voice.
         Err(serenity::Error::Http(HttpError::UnsuccessfulRequest(ErrorResponse {
             error: DiscordJsonError { code: 40032, .. },
             ..
         }))) => {
-            member
-                .user
-                .direct_message(
-                    ctx,
-                    CreateMessage::new()
-                        .content("Voice channel created. You have 1 minute to join."),
+            notify(
+                ctx,
+                Notification::new(
+                    member.user.id,
+                    "Voice channel created. You have 1 minute to join.",
                 )
-                .await
-                .unwrap();
+                .channel(vc.id),
+            )
+            .await;
 
-            if delete_voice_channel_if_inactive(ctx, guild_id, member.user.id, &vc).await {
-                return Ok(());
-            }
+            true
         }
         result => {
-            result.unwrap();
+            result?;
+            false
         }
     };
 
-    let row = VoiceChannelRow::new(vc.id, new.user_id);
+    let mut row = VoiceChannelRow::new(vc.id, guild_id, new.user_id);
+    row.set_mode(mode);
+    row.set_restrictions(&restrictions);
     row.save::<Db, ChannelManager>(pool).await?;
 
+    Metrics::channel_created::<Db, ChannelManager>(ctx, pool, guild_id).await;
+
+    send_welcome(ctx, &guild_data, vc.id, new.user_id).await;
+
+    AuditLog::record::<Db, GuildManager>(
+        ctx,
+        pool,
+        AuditEvent::new(guild_id, vc.id, new.user_id, AuditAction::AutoCreate),
+    )
+    .await;
+
+    reorder_category::<Db, ChannelManager>(ctx, pool, &guild_data, category).await;
+
+    if grace_period {
+        spawn_grace_period(ctx, guild_id, member.user.id, vc);
+    }
+
+    Ok(())
+}
+
+/// Disconnects a member who isn't allowed to create a channel and lets them
+/// know why.
+async fn reject(ctx: &Context, guild_id: GuildId, member: &Member, reason: Error) -> Result<()> {
+    guild_id.disconnect_member(ctx, member.user.id).await?;
+
+    notify(ctx, Notification::new(member.user.id, reason.to_string())).await;
+
     Ok(())
 }
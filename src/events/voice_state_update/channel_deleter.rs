@@ -1,9 +1,17 @@
 use serenity::all::{Context, DiscordJsonError, ErrorResponse, HttpError};
 use sqlx::{Database, Pool};
+use tracing::warn;
 
-use crate::{CachedState, Result, TempVoiceGuildManager, VoiceChannelManager, VoiceStateCache};
+use crate::category::cleanup_category;
+use crate::hibernation::hibernate;
+use crate::metrics::{Metrics, timed};
+use crate::ordering::reorder_category;
+use crate::{
+    AuditAction, AuditEvent, AuditLog, CachedState, Result, TempVoiceGuildManager,
+    VoiceChannelManager, VoiceStateCache,
+};
 
-pub async fn channel_deleter<
+pub(super) async fn channel_deleter<
     Db: Database,
     GuildManager: TempVoiceGuildManager<Db>,
     ChannelManager: VoiceChannelManager<Db>,
@@ -31,7 +39,7 @@ pub async fn channel_deleter<
         None => return Ok(()),
     };
 
-    if row.is_persistent() {
+    if row.is_persistent() && !guild_data.hibernate() {
         return Ok(());
     }
 
@@ -46,48 +54,58 @@ pub async fn channel_deleter<
             error: DiscordJsonError { code: 50001, .. },
             ..
         }))) => {
+            warn!(%channel_id, "channel unavailable, skipping deletion");
             return Ok(());
         }
         r => r?,
     };
-    let category = guild_data.category();
+    let channel = channel.guild().expect("Should be in a guild");
+    let category = channel.parent_id.expect("Should be in a category");
 
-    if channel
-        .guild()
-        .expect("Should be in a guild")
-        .parent_id
-        .expect("Should be in a category")
-        != category
-    {
+    if !guild_data.is_temp_category(category) {
         return Ok(());
     }
 
-    let users = {
-        let data = ctx.data.read().await;
-        let cache = data
-            .get::<VoiceStateCache>()
-            .expect("Expected VoiceStateCache in TypeMap");
-
-        cache
-            .values()
-            .filter(|id| id.channel_id == Some(channel_id))
-            .count()
-    };
+    let users = VoiceStateCache::get(ctx)
+        .await
+        .member_count(old.guild_id, channel_id);
 
     if users == 0 {
-        row.delete::<Db, ChannelManager>(pool).await?;
+        if row.is_persistent() {
+            hibernate::<Db, GuildManager, ChannelManager>(ctx, pool, row, &channel, old.user_id)
+                .await?;
+        } else {
+            row.delete::<Db, ChannelManager>(pool).await?;
+
+            match timed(ctx, "delete_channel", channel_id.delete(ctx)).await {
+                Err(serenity::Error::Http(HttpError::UnsuccessfulRequest(ErrorResponse {
+                    error: DiscordJsonError { code: 10003, .. },
+                    ..
+                }))) => {
+                    warn!(%channel_id, "channel already deleted");
+                }
+                result => {
+                    result?;
+                }
+            };
+
+            Metrics::channel_deleted::<Db, ChannelManager>(ctx, pool, old.guild_id).await;
+
+            AuditLog::record::<Db, GuildManager>(
+                ctx,
+                pool,
+                AuditEvent::new(
+                    old.guild_id,
+                    channel_id,
+                    old.user_id,
+                    AuditAction::AutoDelete,
+                ),
+            )
+            .await;
+        }
 
-        match channel_id.delete(ctx).await {
-            Err(serenity::Error::Http(HttpError::UnsuccessfulRequest(ErrorResponse {
-                error: DiscordJsonError { code: 10003, .. },
-                ..
-            }))) => {
-                // Channel already deleted, ignore this error
-            }
-            result => {
-                result.unwrap();
-            }
-        };
+        reorder_category::<Db, ChannelManager>(ctx, pool, &guild_data, category).await;
+        cleanup_category::<Db, GuildManager>(ctx, pool, guild_data, category, channel_id).await?;
     }
 
     Ok(())
@@ -1,5 +1,81 @@
-pub mod channel_creator;
-pub mod channel_deleter;
+mod channel_activity;
+mod channel_creator;
+mod channel_deleter;
+mod owner_departure;
+mod session_tracker;
+mod spectator_joiner;
 
-pub use channel_creator::channel_creator;
-pub use channel_deleter::channel_deleter;
+use channel_activity::channel_activity;
+use channel_creator::channel_creator;
+use channel_deleter::channel_deleter;
+use owner_departure::owner_departure;
+use serenity::all::{Context, VoiceState};
+use session_tracker::session_tracker;
+use spectator_joiner::spectator_joiner;
+use sqlx::{Database, Pool};
+use tracing::{Instrument, info_span, warn};
+
+use crate::metrics::Metrics;
+use crate::{
+    CachedState, Result, TempVoiceGuildManager, VoiceChannelManager, VoiceStateCache,
+    VoiceStatsManager,
+};
+
+pub async fn voice_state_update<
+    Db: Database,
+    GuildManager: TempVoiceGuildManager<Db>,
+    ChannelManager: VoiceChannelManager<Db>,
+    StatsManager: VoiceStatsManager<Db>,
+>(
+    ctx: &Context,
+    pool: &Pool<Db>,
+    new: &VoiceState,
+) -> Result<()> {
+    let old = VoiceStateCache::get(ctx).await.update(new);
+
+    // Ignore updates that don't move the user, e.g. mute or deafen toggles.
+    if old.as_ref().and_then(|old| old.channel_id) == new.channel_id {
+        return Ok(());
+    }
+
+    let span = info_span!(
+        "voice_state_update",
+        guild_id = ?new.guild_id,
+        channel_id = ?new.channel_id,
+        old_channel_id = ?old.as_ref().and_then(|old| old.channel_id),
+        user_id = %new.user_id,
+    );
+
+    let result =
+        run_stages::<Db, GuildManager, ChannelManager, StatsManager>(ctx, pool, old.as_ref(), new)
+            .instrument(span.clone())
+            .await;
+
+    if let Err(e) = &result {
+        span.in_scope(|| warn!(error = ?e, "voice state update failed"));
+        Metrics::get(ctx).await.error(e);
+    }
+
+    result
+}
+
+async fn run_stages<
+    Db: Database,
+    GuildManager: TempVoiceGuildManager<Db>,
+    ChannelManager: VoiceChannelManager<Db>,
+    StatsManager: VoiceStatsManager<Db>,
+>(
+    ctx: &Context,
+    pool: &Pool<Db>,
+    old: Option<&CachedState>,
+    new: &VoiceState,
+) -> Result<()> {
+    channel_activity::<Db, ChannelManager>(pool, old, new).await?;
+    session_tracker::<Db, ChannelManager, StatsManager>(ctx, pool, new).await?;
+    channel_creator::<Db, GuildManager, ChannelManager>(ctx, pool, new).await?;
+    spectator_joiner::<Db, ChannelManager>(ctx, pool, new).await?;
+    channel_deleter::<Db, GuildManager, ChannelManager>(ctx, pool, old).await?;
+    owner_departure::<Db, ChannelManager>(ctx, pool, old).await?;
+
+    Ok(())
+}
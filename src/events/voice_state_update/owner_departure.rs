@@ -0,0 +1,50 @@
+use serenity::all::{Context, CreateMessage, Mentionable};
+use sqlx::{Database, Pool};
+use tracing::warn;
+
+use crate::{CachedState, Result, VoiceChannelManager, VoiceStateCache};
+
+pub async fn owner_departure<Db: Database, ChannelManager: VoiceChannelManager<Db>>(
+    ctx: &Context,
+    pool: &Pool<Db>,
+    old: Option<&CachedState>,
+) -> Result<()> {
+    let Some(CachedState {
+        channel_id: Some(channel_id),
+        guild_id,
+        user_id,
+    }) = old
+    else {
+        return Ok(());
+    };
+
+    let row = match ChannelManager::get(pool, *channel_id).await.unwrap() {
+        Some(row) if row.is_owner(*user_id) => row,
+        _ => return Ok(()),
+    };
+
+    let users = VoiceStateCache::get(ctx)
+        .await
+        .member_count(*guild_id, *channel_id);
+
+    // The channel deleter handles empty channels.
+    if users == 0 {
+        return Ok(());
+    }
+
+    // The channel works fine without the notice.
+    if let Err(e) = channel_id
+        .send_message(
+            ctx,
+            CreateMessage::new().content(format!(
+                "{} has left the channel. Use `/voice claim` to take ownership.",
+                row.owner_id().mention()
+            )),
+        )
+        .await
+    {
+        warn!(error = ?e, %channel_id, "failed to post owner departure notice");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use serenity::all::{Context, VoiceState};
+use sqlx::{Database, Pool};
+
+use crate::stats::VoiceSessions;
+use crate::{
+    ChannelSession, Result, UserStats, VoiceChannelManager, VoiceChannelRow, VoiceStateCache,
+    VoiceStatsManager,
+};
+
+/// Records how long members spend in temporary channels and how busy each
+/// channel gets. Runs before the channel deleter so the channel's row is still
+/// around when its last member leaves.
+pub(super) async fn session_tracker<
+    Db: Database,
+    ChannelManager: VoiceChannelManager<Db>,
+    StatsManager: VoiceStatsManager<Db>,
+>(
+    ctx: &Context,
+    pool: &Pool<Db>,
+    new: &VoiceState,
+) -> Result<()> {
+    let guild_id = new
+        .guild_id
+        .expect("Should be in a guild as voice channels are guild only");
+
+    let sessions = VoiceSessions::get(ctx).await;
+
+    if let Some((channel_id, seconds)) = sessions.leave(guild_id, new.user_id)
+        && let Some(row) = ChannelManager::get(pool, channel_id).await.unwrap()
+    {
+        let mut user = StatsManager::get_user(pool, guild_id, new.user_id)
+            .await
+            .unwrap()
+            .unwrap_or_else(|| UserStats::new(guild_id, new.user_id));
+        user.add_session(seconds);
+        user.save::<Db, StatsManager>(pool).await?;
+
+        let mut session = session::<Db, StatsManager>(pool, &row).await;
+        session.total_seconds += seconds;
+        session.save::<Db, StatsManager>(pool).await?;
+    }
+
+    let Some(channel_id) = new.channel_id else {
+        return Ok(());
+    };
+
+    // Every channel is tracked since a new temporary channel's row may not be
+    // saved yet when its owner is moved in.
+    sessions.join(guild_id, new.user_id, channel_id);
+
+    if let Some(row) = ChannelManager::get(pool, channel_id).await.unwrap() {
+        let members = VoiceStateCache::get(ctx)
+            .await
+            .member_count(guild_id, channel_id);
+
+        let mut session = session::<Db, StatsManager>(pool, &row).await;
+        session.record_members(members);
+        session.save::<Db, StatsManager>(pool).await?;
+    }
+
+    Ok(())
+}
+
+async fn session<Db: Database, StatsManager: VoiceStatsManager<Db>>(
+    pool: &Pool<Db>,
+    row: &VoiceChannelRow,
+) -> ChannelSession {
+    StatsManager::get_session(pool, row.channel_id())
+        .await
+        .unwrap()
+        .unwrap_or_else(|| ChannelSession::new(row.channel_id(), row.guild_id(), row.owner_id()))
+}
@@ -0,0 +1,29 @@
+use serenity::all::{Context, VoiceState};
+use sqlx::{Database, Pool};
+
+use crate::privacy::grant_speak;
+use crate::{Result, VoiceChannelManager, VoiceChannelMode};
+
+/// Makes sure the owner, trusted users and promoted speakers can talk when
+/// they join a spectator channel. Everyone else is muted by the channel's
+/// `@everyone` overwrite.
+pub(super) async fn spectator_joiner<Db: Database, ChannelManager: VoiceChannelManager<Db>>(
+    ctx: &Context,
+    pool: &Pool<Db>,
+    new: &VoiceState,
+) -> Result<()> {
+    let Some(channel_id) = new.channel_id else {
+        return Ok(());
+    };
+
+    let row = match ChannelManager::get(pool, channel_id).await.unwrap() {
+        Some(row) if row.mode() == VoiceChannelMode::Spectator => row,
+        _ => return Ok(()),
+    };
+
+    if row.can_speak(new.user_id) {
+        grant_speak(ctx, channel_id, new.user_id).await?;
+    }
+
+    Ok(())
+}
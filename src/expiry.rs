@@ -0,0 +1,163 @@
+use serenity::all::{
+    Context, DiscordJsonError, ErrorResponse, GuildId, HttpError, Mentionable, Timestamp,
+};
+use sqlx::{Database, Pool};
+use tracing::warn;
+
+use crate::metrics::{Metrics, timed};
+use crate::notifier::{Notification, notify};
+use crate::{
+    AuditAction, AuditEvent, AuditLog, ExpiryAction, Result, TempVoiceGuildManager,
+    VoiceChannelManager, VoiceChannelRow, VoiceStateCache,
+};
+
+/// Owners are warned this many seconds before their channel expires.
+const WARNING_PERIOD: i64 = 24 * 60 * 60;
+
+/// Expires persistent channels that have been idle for longer than the guild
+/// allows, warning their owners a day beforehand.
+///
+/// This isn't driven by any gateway event, so it should be run periodically
+/// (e.g. hourly) for every guild.
+pub async fn expire_idle_channels<
+    Db: Database,
+    GuildManager: TempVoiceGuildManager<Db>,
+    ChannelManager: VoiceChannelManager<Db>,
+>(
+    ctx: &Context,
+    pool: &Pool<Db>,
+    guild_id: GuildId,
+) -> Result<()> {
+    let result = expire_guild::<Db, GuildManager, ChannelManager>(ctx, pool, guild_id).await;
+
+    Metrics::track(ctx, result).await
+}
+
+async fn expire_guild<
+    Db: Database,
+    GuildManager: TempVoiceGuildManager<Db>,
+    ChannelManager: VoiceChannelManager<Db>,
+>(
+    ctx: &Context,
+    pool: &Pool<Db>,
+    guild_id: GuildId,
+) -> Result<()> {
+    let Ok(guild_data) = GuildManager::get(pool, guild_id).await else {
+        return Ok(());
+    };
+
+    let Some(expiry) = guild_data.idle_expiry() else {
+        return Ok(());
+    };
+
+    let cache = VoiceStateCache::get(ctx).await;
+    let rows = ChannelManager::get_persistent(pool, guild_id)
+        .await
+        .unwrap();
+
+    for mut row in rows {
+        // A channel in use isn't idle, however long ago someone last joined.
+        if cache.member_count(guild_id, row.channel_id()) > 0 {
+            continue;
+        }
+
+        let idle = row.idle_for();
+
+        if idle >= expiry {
+            expire::<Db, GuildManager, ChannelManager>(ctx, pool, guild_data.expiry_action(), row)
+                .await?;
+        } else if idle >= expiry - WARNING_PERIOD && !row.expiry_warned {
+            warn(ctx, guild_data.expiry_action(), &row, expiry - idle).await;
+
+            row.expiry_warned = true;
+            row.save::<Db, ChannelManager>(pool).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn expire<
+    Db: Database,
+    GuildManager: TempVoiceGuildManager<Db>,
+    ChannelManager: VoiceChannelManager<Db>,
+>(
+    ctx: &Context,
+    pool: &Pool<Db>,
+    action: ExpiryAction,
+    mut row: VoiceChannelRow,
+) -> Result<()> {
+    let guild_id = row.guild_id();
+    let channel_id = row.channel_id();
+    let owner_id = row.owner_id();
+
+    // Hibernated channels have nothing left to unpersist, so they're always
+    // deleted.
+    if action == ExpiryAction::Unpersist && !row.is_hibernated() {
+        row.toggle_persist();
+        row.save::<Db, ChannelManager>(pool).await?;
+    } else {
+        let hibernated = row.is_hibernated();
+        row.delete::<Db, ChannelManager>(pool).await?;
+
+        if !hibernated {
+            match timed(ctx, "delete_channel", channel_id.delete(ctx)).await {
+                Err(serenity::Error::Http(HttpError::UnsuccessfulRequest(ErrorResponse {
+                    error: DiscordJsonError { code: 10003, .. },
+                    ..
+                }))) => {
+                    warn!(%channel_id, "channel already deleted");
+                }
+                result => {
+                    result?;
+                }
+            };
+
+            Metrics::channel_deleted::<Db, ChannelManager>(ctx, pool, guild_id).await;
+        }
+    }
+
+    AuditLog::record::<Db, GuildManager>(
+        ctx,
+        pool,
+        AuditEvent::new(guild_id, channel_id, owner_id, AuditAction::Expire),
+    )
+    .await;
+
+    Ok(())
+}
+
+async fn warn(ctx: &Context, action: ExpiryAction, row: &VoiceChannelRow, remaining: i64) {
+    let expires_at = Timestamp::now().unix_timestamp() + remaining;
+
+    let notification = match row.snapshot() {
+        Some(snapshot) => {
+            let name = match snapshot {
+                Ok(snapshot) => format!(" **{}**", snapshot.name),
+                Err(_) => String::new(),
+            };
+
+            Notification::new(
+                row.owner_id(),
+                format!(
+                    "Your hibernated voice channel{name} hasn't been used in a while and will be deleted <t:{expires_at}:R>. Use `/voice restore` to keep it."
+                ),
+            )
+        }
+        None => {
+            let outcome = match action {
+                ExpiryAction::Unpersist => "stop being persistent",
+                ExpiryAction::Delete => "be deleted",
+            };
+
+            let content = format!(
+                "Your voice channel {} hasn't been used in a while and will {outcome} <t:{expires_at}:R>. Join it to keep it.",
+                row.channel_id().mention()
+            );
+
+            Notification::new(row.owner_id(), content).channel(row.channel_id())
+        }
+    };
+
+    notify(ctx, notification).await;
+}
@@ -1,8 +1,11 @@
 use async_trait::async_trait;
-use serenity::all::{ChannelId, GuildId};
+use serenity::all::{ChannelId, GuildId, Member, RoleId, Timestamp, UserId};
 use sqlx::any::AnyQueryResult;
 use sqlx::{Database, FromRow, Pool};
 
+use crate::audit::AuditEvent;
+use crate::{ChannelRestriction, Result, VoiceChannelMode};
+
 #[async_trait]
 pub trait TempVoiceGuildManager<Db: Database> {
     async fn save(
@@ -17,13 +20,74 @@ pub trait TempVoiceGuildManager<Db: Database> {
     async fn get_category(pool: &Pool<Db>, id: GuildId) -> sqlx::Result<ChannelId>;
 
     async fn get_creator_channel(pool: &Pool<Db>, id: GuildId) -> sqlx::Result<Option<ChannelId>>;
+
+    async fn update(pool: &Pool<Db>, row: TempVoiceRow) -> sqlx::Result<AnyQueryResult>;
+
+    async fn get_ban(
+        pool: &Pool<Db>,
+        id: GuildId,
+        user_id: UserId,
+    ) -> sqlx::Result<Option<TempVoiceBan>>;
+
+    async fn save_ban(pool: &Pool<Db>, ban: TempVoiceBan) -> sqlx::Result<AnyQueryResult>;
+
+    async fn delete_ban(
+        pool: &Pool<Db>,
+        id: GuildId,
+        user_id: UserId,
+    ) -> sqlx::Result<AnyQueryResult>;
+
+    async fn get_last_creation(
+        pool: &Pool<Db>,
+        id: GuildId,
+        user_id: UserId,
+    ) -> sqlx::Result<Option<i64>>;
+
+    async fn save_last_creation(
+        pool: &Pool<Db>,
+        id: GuildId,
+        user_id: UserId,
+        created_at: i64,
+    ) -> sqlx::Result<AnyQueryResult>;
+
+    async fn delete(pool: &Pool<Db>, id: GuildId) -> sqlx::Result<AnyQueryResult>;
+
+    /// Persists an audit log event. Events are only posted to the log channel
+    /// unless this is implemented.
+    async fn save_audit_event(_pool: &Pool<Db>, _event: &AuditEvent) -> sqlx::Result<()> {
+        Ok(())
+    }
 }
 
-#[derive(FromRow)]
+#[derive(FromRow, Clone)]
 pub struct TempVoiceRow {
     pub id: i64,
     pub temp_voice_category: Option<i64>,
     pub temp_voice_creator_channel: Option<i64>,
+    pub temp_voice_log_channel: Option<i64>,
+    pub temp_voice_moderator_role: Option<i64>,
+    pub temp_voice_creation_cooldown: Option<i64>,
+    pub temp_voice_max_owned_channels: Option<i64>,
+    pub temp_voice_name_blocklist: Vec<String>,
+    pub temp_voice_name_pattern: Option<String>,
+    pub temp_voice_overflow_categories: Vec<i64>,
+    pub temp_voice_auto_categories: Vec<i64>,
+    pub temp_voice_auto_overflow: bool,
+    pub temp_voice_channel_order: ChannelOrder,
+    pub temp_voice_pin_persistent: bool,
+    pub temp_voice_hibernate: bool,
+    pub temp_voice_idle_expiry: Option<i64>,
+    pub temp_voice_expiry_action: ExpiryAction,
+    pub temp_voice_persist_quota: Option<i64>,
+    pub temp_voice_persist_quota_roles: Vec<i64>,
+    pub temp_voice_persist_quotas: Vec<i64>,
+    pub temp_voice_premium_roles: Vec<i64>,
+    pub temp_voice_premium_features: Vec<String>,
+    pub temp_voice_welcome: bool,
+    pub temp_voice_welcome_message: Option<String>,
+    pub temp_voice_welcome_panel: bool,
+    pub temp_voice_default_mode: VoiceChannelMode,
+    pub temp_voice_default_restrictions: Vec<String>,
 }
 
 impl TempVoiceRow {
@@ -38,4 +102,422 @@ impl TempVoiceRow {
     pub fn creator_channel(&self) -> ChannelId {
         ChannelId::from(self.temp_voice_creator_channel.unwrap() as u64)
     }
+
+    /// Every category temporary channels may be created in, in the order they
+    /// are filled: the main category, configured overflow categories, then
+    /// categories the bot created itself.
+    pub fn categories(&self) -> Vec<ChannelId> {
+        self.temp_voice_category
+            .iter()
+            .chain(&self.temp_voice_overflow_categories)
+            .chain(&self.temp_voice_auto_categories)
+            .map(|id| ChannelId::from(*id as u64))
+            .collect()
+    }
+
+    pub fn is_temp_category(&self, id: impl Into<ChannelId>) -> bool {
+        self.categories().contains(&id.into())
+    }
+
+    pub fn add_overflow_category(&mut self, id: impl Into<ChannelId>) {
+        let id = id.into().get() as i64;
+
+        if !self.temp_voice_overflow_categories.contains(&id) {
+            self.temp_voice_overflow_categories.push(id);
+        }
+    }
+
+    pub fn is_auto_category(&self, id: impl Into<ChannelId>) -> bool {
+        self.temp_voice_auto_categories
+            .contains(&(id.into().get() as i64))
+    }
+
+    pub fn add_auto_category(&mut self, id: impl Into<ChannelId>) {
+        self.temp_voice_auto_categories.push(id.into().get() as i64);
+    }
+
+    pub fn remove_category(&mut self, id: impl Into<ChannelId>) {
+        let id = id.into().get() as i64;
+
+        self.temp_voice_overflow_categories
+            .retain(|category| *category != id);
+        self.temp_voice_auto_categories
+            .retain(|category| *category != id);
+    }
+
+    pub fn auto_overflow(&self) -> bool {
+        self.temp_voice_auto_overflow
+    }
+
+    pub fn set_auto_overflow(&mut self, enabled: bool) {
+        self.temp_voice_auto_overflow = enabled;
+    }
+
+    pub fn channel_order(&self) -> ChannelOrder {
+        self.temp_voice_channel_order
+    }
+
+    pub fn set_channel_order(&mut self, order: ChannelOrder) {
+        self.temp_voice_channel_order = order;
+    }
+
+    pub fn pin_persistent(&self) -> bool {
+        self.temp_voice_pin_persistent
+    }
+
+    pub fn set_pin_persistent(&mut self, enabled: bool) {
+        self.temp_voice_pin_persistent = enabled;
+    }
+
+    /// Whether persistent channels are archived once their last member leaves.
+    pub fn hibernate(&self) -> bool {
+        self.temp_voice_hibernate
+    }
+
+    pub fn set_hibernate(&mut self, enabled: bool) {
+        self.temp_voice_hibernate = enabled;
+    }
+
+    /// Seconds a persistent channel may go without activity before it
+    /// expires.
+    pub fn idle_expiry(&self) -> Option<i64> {
+        self.temp_voice_idle_expiry.filter(|seconds| *seconds > 0)
+    }
+
+    pub fn set_idle_expiry(&mut self, seconds: i64) {
+        self.temp_voice_idle_expiry = Some(seconds);
+    }
+
+    pub fn expiry_action(&self) -> ExpiryAction {
+        self.temp_voice_expiry_action
+    }
+
+    pub fn set_expiry_action(&mut self, action: ExpiryAction) {
+        self.temp_voice_expiry_action = action;
+    }
+
+    /// The number of persistent channels a member may own, or `None` if
+    /// unlimited. Members get the most generous quota out of the guild default
+    /// and each of their roles, where 0 means unlimited.
+    pub fn persist_quota(&self, member: &Member) -> Option<i64> {
+        let quotas = self
+            .temp_voice_persist_quota_roles
+            .iter()
+            .zip(&self.temp_voice_persist_quotas)
+            .filter(|(role, _)| member.roles.contains(&RoleId::from(**role as u64)))
+            .map(|(_, quota)| *quota)
+            .chain([self.temp_voice_persist_quota.unwrap_or(1)])
+            .collect::<Vec<_>>();
+
+        if quotas.contains(&0) {
+            return None;
+        }
+
+        quotas.into_iter().max()
+    }
+
+    pub fn set_default_persist_quota(&mut self, quota: i64) {
+        self.temp_voice_persist_quota = Some(quota);
+    }
+
+    /// Sets the quota for members with the role, or removes it if `None`.
+    pub fn set_role_persist_quota(&mut self, role: impl Into<RoleId>, quota: Option<i64>) {
+        let role = role.into().get() as i64;
+
+        if let Some(index) = self
+            .temp_voice_persist_quota_roles
+            .iter()
+            .position(|id| *id == role)
+        {
+            self.temp_voice_persist_quota_roles.remove(index);
+            self.temp_voice_persist_quotas.remove(index);
+        }
+
+        if let Some(quota) = quota {
+            self.temp_voice_persist_quota_roles.push(role);
+            self.temp_voice_persist_quotas.push(quota);
+        }
+    }
+
+    /// Members with any of the premium roles can use every premium feature.
+    pub fn is_premium(&self, member: &Member) -> bool {
+        self.temp_voice_premium_roles
+            .iter()
+            .any(|role| member.roles.contains(&RoleId::from(*role as u64)))
+    }
+
+    pub fn add_premium_role(&mut self, id: impl Into<RoleId>) {
+        let id = id.into().get() as i64;
+
+        if !self.temp_voice_premium_roles.contains(&id) {
+            self.temp_voice_premium_roles.push(id);
+        }
+    }
+
+    pub fn remove_premium_role(&mut self, id: impl Into<RoleId>) {
+        let id = id.into().get() as i64;
+
+        self.temp_voice_premium_roles.retain(|role| *role != id);
+    }
+
+    pub fn is_premium_feature(&self, feature: PremiumFeature) -> bool {
+        self.temp_voice_premium_features
+            .iter()
+            .any(|gated| gated == feature.as_str())
+    }
+
+    pub fn set_premium_feature(&mut self, feature: PremiumFeature, gated: bool) {
+        self.temp_voice_premium_features
+            .retain(|gated| gated != feature.as_str());
+
+        if gated {
+            self.temp_voice_premium_features
+                .push(feature.as_str().to_string());
+        }
+    }
+
+    pub fn welcome(&self) -> bool {
+        self.temp_voice_welcome
+    }
+
+    pub fn set_welcome(&mut self, enabled: bool) {
+        self.temp_voice_welcome = enabled;
+    }
+
+    /// The guild's own welcome template, if it has one.
+    pub fn welcome_message(&self) -> Option<&str> {
+        self.temp_voice_welcome_message.as_deref()
+    }
+
+    pub fn set_welcome_message(&mut self, message: Option<String>) {
+        self.temp_voice_welcome_message = message;
+    }
+
+    pub fn welcome_panel(&self) -> bool {
+        self.temp_voice_welcome_panel
+    }
+
+    pub fn set_welcome_panel(&mut self, enabled: bool) {
+        self.temp_voice_welcome_panel = enabled;
+    }
+
+    /// The privacy new channels start with.
+    pub fn default_mode(&self) -> VoiceChannelMode {
+        self.temp_voice_default_mode
+    }
+
+    pub fn set_default_mode(&mut self, mode: VoiceChannelMode) {
+        self.temp_voice_default_mode = mode;
+    }
+
+    pub fn default_restrictions(&self) -> Vec<ChannelRestriction> {
+        self.temp_voice_default_restrictions
+            .iter()
+            .filter_map(|name| ChannelRestriction::from_name(name))
+            .collect()
+    }
+
+    pub fn set_default_restriction(&mut self, restriction: ChannelRestriction, enabled: bool) {
+        self.temp_voice_default_restrictions
+            .retain(|name| name != restriction.as_str());
+
+        if enabled {
+            self.temp_voice_default_restrictions
+                .push(restriction.as_str().to_string());
+        }
+    }
+
+    pub fn set_creator_channel(&mut self, id: impl Into<ChannelId>) {
+        self.temp_voice_creator_channel = Some(id.into().get() as i64);
+    }
+
+    pub fn log_channel(&self) -> Option<ChannelId> {
+        self.temp_voice_log_channel
+            .map(|id| ChannelId::from(id as u64))
+    }
+
+    pub fn set_log_channel(&mut self, id: Option<ChannelId>) {
+        self.temp_voice_log_channel = id.map(|id| id.get() as i64);
+    }
+
+    pub fn moderator_role(&self) -> Option<RoleId> {
+        self.temp_voice_moderator_role
+            .map(|id| RoleId::from(id as u64))
+    }
+
+    pub fn set_moderator_role(&mut self, id: Option<RoleId>) {
+        self.temp_voice_moderator_role = id.map(|id| id.get() as i64);
+    }
+
+    pub fn creation_cooldown(&self) -> Option<i64> {
+        self.temp_voice_creation_cooldown
+            .filter(|seconds| *seconds > 0)
+    }
+
+    pub fn set_creation_cooldown(&mut self, seconds: i64) {
+        self.temp_voice_creation_cooldown = Some(seconds);
+    }
+
+    pub fn max_owned_channels(&self) -> Option<i64> {
+        self.temp_voice_max_owned_channels
+            .filter(|limit| *limit > 0)
+    }
+
+    pub fn set_max_owned_channels(&mut self, limit: i64) {
+        self.temp_voice_max_owned_channels = Some(limit);
+    }
+
+    pub fn name_blocklist(&self) -> &[String] {
+        &self.temp_voice_name_blocklist
+    }
+
+    pub fn block_name(&mut self, word: impl Into<String>) {
+        let word = word.into().to_lowercase();
+
+        if !self.temp_voice_name_blocklist.contains(&word) {
+            self.temp_voice_name_blocklist.push(word);
+        }
+    }
+
+    pub fn allow_name(&mut self, word: &str) {
+        let word = word.to_lowercase();
+
+        self.temp_voice_name_blocklist
+            .retain(|blocked| *blocked != word);
+    }
+
+    pub fn name_pattern(&self) -> Option<&str> {
+        self.temp_voice_name_pattern.as_deref()
+    }
+
+    pub fn set_name_pattern(&mut self, pattern: Option<String>) {
+        self.temp_voice_name_pattern = pattern;
+    }
+
+    /// Moderators can use every subcommand on any temporary channel, either
+    /// through the configured moderator role or by being able to manage the
+    /// channels and their members themselves.
+    pub fn is_moderator(&self, member: &Member) -> bool {
+        let permissions = member.permissions.unwrap_or_default();
+
+        permissions.manage_channels()
+            || permissions.move_members()
+            || self
+                .moderator_role()
+                .is_some_and(|role| member.roles.contains(&role))
+    }
+
+    pub async fn save<Db: Database, Manager: TempVoiceGuildManager<Db>>(
+        self,
+        pool: &Pool<Db>,
+    ) -> Result<()> {
+        Manager::update(pool, self).await.unwrap();
+
+        Ok(())
+    }
+}
+
+/// How temporary channels are arranged within their category.
+#[derive(sqlx::Type, Clone, Copy, PartialEq, Eq)]
+#[sqlx(rename_all = "lowercase")]
+pub enum ChannelOrder {
+    /// Leave channels wherever Discord puts them.
+    Discord,
+    /// Newest channels directly below the creator channel.
+    Creator,
+    /// Channels with the most members first.
+    Members,
+    /// Oldest channels first.
+    Created,
+}
+
+/// What happens to a persistent channel once it has been idle for too long.
+#[derive(sqlx::Type, Clone, Copy, PartialEq, Eq)]
+#[sqlx(rename_all = "lowercase")]
+pub enum ExpiryAction {
+    /// Turn it back into a temporary channel, deleted the next time it
+    /// empties.
+    Unpersist,
+    /// Delete the channel straight away.
+    Delete,
+}
+
+/// Channel features a guild can limit to its premium roles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PremiumFeature {
+    /// Bitrates above what every guild gets without boosts.
+    HighBitrate,
+    Persist,
+    Region,
+}
+
+impl PremiumFeature {
+    pub fn from_name(name: &str) -> Option<Self> {
+        let feature = match name {
+            "high_bitrate" => Self::HighBitrate,
+            "persist" => Self::Persist,
+            "region" => Self::Region,
+            _ => return None,
+        };
+
+        Some(feature)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::HighBitrate => "high_bitrate",
+            Self::Persist => "persist",
+            Self::Region => "region",
+        }
+    }
+}
+
+#[derive(FromRow)]
+pub struct TempVoiceBan {
+    pub guild_id: i64,
+    pub user_id: i64,
+    pub expires_at: Option<i64>,
+}
+
+impl TempVoiceBan {
+    pub fn new(
+        guild_id: impl Into<GuildId>,
+        user_id: impl Into<UserId>,
+        expires_at: Option<Timestamp>,
+    ) -> Self {
+        Self {
+            guild_id: guild_id.into().get() as i64,
+            user_id: user_id.into().get() as i64,
+            expires_at: expires_at.map(|timestamp| timestamp.unix_timestamp()),
+        }
+    }
+
+    pub fn guild_id(&self) -> GuildId {
+        GuildId::new(self.guild_id as u64)
+    }
+
+    pub fn user_id(&self) -> UserId {
+        UserId::new(self.user_id as u64)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= Timestamp::now().unix_timestamp())
+    }
+
+    /// Returns the user's ban if it is still in effect, clearing it once it
+    /// has expired.
+    pub async fn get_active<Db: Database, Manager: TempVoiceGuildManager<Db>>(
+        pool: &Pool<Db>,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Option<Self>> {
+        match Manager::get_ban(pool, guild_id, user_id).await.unwrap() {
+            Some(ban) if ban.is_expired() => {
+                Manager::delete_ban(pool, guild_id, user_id).await.unwrap();
+                Ok(None)
+            }
+            ban => Ok(ban),
+        }
+    }
 }
@@ -0,0 +1,128 @@
+use serenity::all::{
+    ChannelType, Context, CreateChannel, DiscordJsonError, ErrorResponse, GuildChannel, HttpError,
+    UserId,
+};
+use sqlx::{Database, Pool};
+use tracing::warn;
+
+use crate::category::available_category;
+use crate::metrics::{Metrics, timed};
+use crate::ordering::reorder_category;
+use crate::{
+    AuditAction, AuditEvent, AuditLog, Error, Result, TempVoiceGuildManager, TempVoiceRow,
+    VoiceChannelManager, VoiceChannelRow,
+};
+
+/// Archives an idle persistent channel. The Discord channel is deleted while
+/// the row keeps a snapshot of its settings so it can be restored later.
+pub(crate) async fn hibernate<
+    Db: Database,
+    GuildManager: TempVoiceGuildManager<Db>,
+    ChannelManager: VoiceChannelManager<Db>,
+>(
+    ctx: &Context,
+    pool: &Pool<Db>,
+    mut row: VoiceChannelRow,
+    channel: &GuildChannel,
+    user_id: UserId,
+) -> Result<()> {
+    row.hibernate(channel);
+    row.save::<Db, ChannelManager>(pool).await?;
+
+    match timed(ctx, "delete_channel", channel.delete(ctx)).await {
+        Err(serenity::Error::Http(HttpError::UnsuccessfulRequest(ErrorResponse {
+            error: DiscordJsonError { code: 10003, .. },
+            ..
+        }))) => {
+            warn!(channel_id = %channel.id, "channel already deleted");
+        }
+        result => {
+            result?;
+        }
+    };
+
+    Metrics::channel_deleted::<Db, ChannelManager>(ctx, pool, channel.guild_id).await;
+
+    AuditLog::record::<Db, GuildManager>(
+        ctx,
+        pool,
+        AuditEvent::new(
+            channel.guild_id,
+            channel.id,
+            user_id,
+            AuditAction::Hibernate,
+        ),
+    )
+    .await;
+
+    Ok(())
+}
+
+/// Recreates a hibernated channel from its snapshot and moves its row over to
+/// the new channel.
+pub(crate) async fn restore<
+    Db: Database,
+    GuildManager: TempVoiceGuildManager<Db>,
+    ChannelManager: VoiceChannelManager<Db>,
+>(
+    ctx: &Context,
+    pool: &Pool<Db>,
+    guild_data: &mut TempVoiceRow,
+    mut row: VoiceChannelRow,
+    user_id: UserId,
+) -> Result<GuildChannel> {
+    let snapshot = match row.snapshot() {
+        Some(Ok(snapshot)) => snapshot,
+        snapshot => {
+            warn!(
+                channel_id = %row.channel_id(),
+                error = ?snapshot.and_then(|snapshot| snapshot.err()),
+                "dropping hibernated channel with an unreadable snapshot"
+            );
+            row.delete::<Db, ChannelManager>(pool).await?;
+
+            return Err(Error::CorruptSnapshot);
+        }
+    };
+
+    let category = available_category::<Db, GuildManager>(ctx, pool, guild_data).await?;
+
+    let mut builder = CreateChannel::new(snapshot.name)
+        .kind(ChannelType::Voice)
+        .category(category)
+        .permissions(snapshot.permission_overwrites);
+
+    if let Some(user_limit) = snapshot.user_limit {
+        builder = builder.user_limit(user_limit);
+    }
+
+    if let Some(bitrate) = snapshot.bitrate {
+        builder = builder.bitrate(bitrate);
+    }
+
+    if let Some(region) = snapshot.rtc_region {
+        builder = builder.rtc_region(region);
+    }
+
+    let guild_id = guild_data.guild_id();
+    let vc = timed(ctx, "create_channel", guild_id.create_channel(ctx, builder)).await?;
+
+    ChannelManager::delete(pool, row.channel_id())
+        .await
+        .unwrap();
+    row.restore(vc.id);
+    row.save::<Db, ChannelManager>(pool).await?;
+
+    Metrics::channel_created::<Db, ChannelManager>(ctx, pool, guild_id).await;
+
+    AuditLog::record::<Db, GuildManager>(
+        ctx,
+        pool,
+        AuditEvent::new(guild_id, vc.id, user_id, AuditAction::Restore),
+    )
+    .await;
+
+    reorder_category::<Db, ChannelManager>(ctx, pool, guild_data, category).await;
+
+    Ok(vc)
+}
@@ -1,78 +1,66 @@
+pub mod audit;
+mod category;
 pub mod commands;
+pub mod control_panel;
+pub mod creation_limits;
+mod creator_channel;
 mod error;
 pub mod events;
+pub mod expiry;
 pub mod guild_manager;
+mod hibernation;
+pub mod metrics;
+pub mod name_filter;
+pub mod notifier;
+mod ordering;
+mod privacy;
+pub mod stats;
 pub mod voice_channel_manager;
+pub mod voice_state_cache;
+mod welcome;
 
-use std::collections::HashMap;
 use std::time::Duration;
 
 use serenity::all::{
-    ChannelId, Context, GuildChannel, GuildId, LightMethod, Request, Route, UserId, VoiceState,
+    Context, GuildChannel, GuildId, LightMethod, Request, Route, UserId, VoiceState,
 };
-use serenity::prelude::TypeMapKey;
+use tracing::warn;
 
+pub use audit::{AuditAction, AuditEvent, AuditLog};
 pub use commands::VoiceCommand;
+pub use control_panel::ControlPanel;
+pub use creation_limits::CreationLimits;
 pub use error::Error;
 use error::Result;
-pub use guild_manager::{TempVoiceGuildManager, TempVoiceRow};
-pub use voice_channel_manager::{VoiceChannelManager, VoiceChannelRow};
-
-#[derive(Debug)]
-pub struct CachedState {
-    pub channel_id: Option<ChannelId>,
-    pub guild_id: GuildId,
-    pub user_id: UserId,
-}
-
-impl CachedState {
-    pub fn new(channel_id: Option<ChannelId>, guild_id: GuildId, user_id: UserId) -> Self {
-        Self {
-            channel_id,
-            guild_id,
-            user_id,
-        }
-    }
-}
-
-impl From<&VoiceState> for CachedState {
-    fn from(state: &VoiceState) -> Self {
-        Self {
-            channel_id: state.channel_id,
-            guild_id: state.guild_id.unwrap(),
-            user_id: state.user_id,
-        }
-    }
-}
-
-pub struct VoiceStateCache;
-
-impl VoiceStateCache {
-    pub async fn update(ctx: &Context, new: &VoiceState) -> Result<Option<CachedState>> {
-        let mut data = ctx.data.write().await;
-        let cache = data
-            .get_mut::<Self>()
-            .expect("Expected VoiceStateCache in TypeMap");
-
-        let old = if new.channel_id.is_none() {
-            cache.remove(&new.user_id)
-        } else {
-            cache.insert(new.user_id, new.into())
-        };
-
-        Ok(old)
-    }
-}
-
-impl TypeMapKey for VoiceStateCache {
-    type Value = HashMap<UserId, CachedState>;
-}
+pub use expiry::expire_idle_channels;
+pub use guild_manager::{
+    ChannelOrder, ExpiryAction, PremiumFeature, TempVoiceBan, TempVoiceGuildManager, TempVoiceRow,
+};
+pub use metrics::{InMemoryRecorder, MetricsHook, MetricsRecorder};
+pub use name_filter::{NameCheck, NameFilter, NameFilterHook};
+pub use notifier::{DefaultNotifier, Delivery, Notification, Notifier, NotifierHook};
+pub use stats::{ChannelSession, UserStats, VoiceStatsManager};
+pub use voice_channel_manager::{
+    ChannelRestriction, ChannelSnapshot, VoiceChannelManager, VoiceChannelMode, VoiceChannelRow,
+};
+pub use voice_state_cache::{CachedState, VoiceStateCache};
 
 pub async fn get_voice_state(
     ctx: &Context,
     guild_id: GuildId,
     user_id: UserId,
 ) -> serenity::Result<VoiceState> {
+    // Users missing from the cache may still be connected if the guild
+    // isn't cached yet, so fall through to the REST lookup.
+    #[cfg(feature = "cache")]
+    if let Some(state) = ctx
+        .cache
+        .guild(guild_id)
+        .and_then(|guild| guild.voice_states.get(&user_id).cloned())
+    {
+        return Ok(state);
+    }
+
     ctx.http
         .fire::<VoiceState>(Request::new(
             Route::GuildVoiceStates { guild_id, user_id },
@@ -92,8 +80,26 @@ pub async fn delete_voice_channel_if_inactive(
     match get_voice_state(ctx, guild_id, user_id).await {
         Ok(voice_state) if voice_state.channel_id == Some(vc.id) => false,
         _ => {
-            vc.delete(ctx).await.unwrap();
+            if let Err(e) = vc.delete(ctx).await {
+                warn!(error = ?e, channel_id = %vc.id, "failed to delete unused channel");
+            }
             true
         }
     }
 }
+
+/// Runs [`delete_voice_channel_if_inactive`] in the background so the caller
+/// isn't held up for the grace period. The channel's row is removed by the
+/// channel delete event.
+pub(crate) fn spawn_grace_period(
+    ctx: &Context,
+    guild_id: GuildId,
+    user_id: UserId,
+    vc: GuildChannel,
+) {
+    let ctx = ctx.clone();
+
+    tokio::spawn(async move {
+        delete_voice_channel_if_inactive(&ctx, guild_id, user_id, &vc).await;
+    });
+}
@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use serenity::all::{Context, GuildId};
+use serenity::prelude::TypeMapKey;
+use sqlx::{Database, Pool};
+
+use crate::{Error, Result, VoiceChannelManager};
+
+pub const CHANNELS_CREATED: &str = "temp_voice_channels_created_total";
+pub const CHANNELS_DELETED: &str = "temp_voice_channels_deleted_total";
+pub const ACTIVE_CHANNELS: &str = "temp_voice_active_channels";
+pub const COMMAND_INVOCATIONS: &str = "temp_voice_command_invocations_total";
+pub const ERRORS: &str = "temp_voice_errors_total";
+pub const API_LATENCY: &str = "temp_voice_api_latency_seconds";
+
+pub type Labels<'a> = &'a [(&'static str, String)];
+
+/// Receives the crate's counters, gauges and histograms so the host can
+/// forward them to Prometheus, logs or anything else.
+///
+/// Nothing is recorded unless an implementation is registered in the
+/// `TypeMap` under [`MetricsHook`].
+pub trait MetricsRecorder: Send + Sync {
+    fn increment_counter(&self, name: &'static str, labels: Labels);
+
+    fn set_gauge(&self, name: &'static str, labels: Labels, value: f64);
+
+    fn record_histogram(&self, name: &'static str, labels: Labels, value: f64);
+}
+
+pub struct MetricsHook;
+
+impl TypeMapKey for MetricsHook {
+    type Value = Arc<dyn MetricsRecorder>;
+}
+
+/// Keeps every metric in memory, for tests or hosts that poll values
+/// themselves.
+#[derive(Default)]
+pub struct InMemoryRecorder {
+    counters: Mutex<HashMap<MetricKey, u64>>,
+    gauges: Mutex<HashMap<MetricKey, f64>>,
+    histograms: Mutex<HashMap<MetricKey, Vec<f64>>>,
+}
+
+type MetricKey = (&'static str, Vec<(&'static str, String)>);
+
+impl InMemoryRecorder {
+    pub fn counter(&self, name: &'static str, labels: Labels) -> u64 {
+        self.counters
+            .lock()
+            .unwrap()
+            .get(&(name, labels.to_vec()))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn gauge(&self, name: &'static str, labels: Labels) -> Option<f64> {
+        self.gauges
+            .lock()
+            .unwrap()
+            .get(&(name, labels.to_vec()))
+            .copied()
+    }
+
+    pub fn histogram(&self, name: &'static str, labels: Labels) -> Vec<f64> {
+        self.histograms
+            .lock()
+            .unwrap()
+            .get(&(name, labels.to_vec()))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+impl MetricsRecorder for InMemoryRecorder {
+    fn increment_counter(&self, name: &'static str, labels: Labels) {
+        *self
+            .counters
+            .lock()
+            .unwrap()
+            .entry((name, labels.to_vec()))
+            .or_default() += 1;
+    }
+
+    fn set_gauge(&self, name: &'static str, labels: Labels, value: f64) {
+        self.gauges
+            .lock()
+            .unwrap()
+            .insert((name, labels.to_vec()), value);
+    }
+
+    fn record_histogram(&self, name: &'static str, labels: Labels, value: f64) {
+        self.histograms
+            .lock()
+            .unwrap()
+            .entry((name, labels.to_vec()))
+            .or_default()
+            .push(value);
+    }
+}
+
+/// The registered recorder, if any, with helpers for each metric.
+pub(crate) struct Metrics(Option<Arc<dyn MetricsRecorder>>);
+
+impl Metrics {
+    pub(crate) async fn get(ctx: &Context) -> Self {
+        Self(ctx.data.read().await.get::<MetricsHook>().cloned())
+    }
+
+    pub(crate) async fn channel_created<Db: Database, ChannelManager: VoiceChannelManager<Db>>(
+        ctx: &Context,
+        pool: &Pool<Db>,
+        guild_id: GuildId,
+    ) {
+        let metrics = Self::get(ctx).await;
+
+        if let Some(recorder) = &metrics.0 {
+            recorder.increment_counter(CHANNELS_CREATED, &guild_labels(guild_id));
+            metrics
+                .active_channels::<Db, ChannelManager>(pool, guild_id)
+                .await;
+        }
+    }
+
+    pub(crate) async fn channel_deleted<Db: Database, ChannelManager: VoiceChannelManager<Db>>(
+        ctx: &Context,
+        pool: &Pool<Db>,
+        guild_id: GuildId,
+    ) {
+        let metrics = Self::get(ctx).await;
+
+        if let Some(recorder) = &metrics.0 {
+            recorder.increment_counter(CHANNELS_DELETED, &guild_labels(guild_id));
+            metrics
+                .active_channels::<Db, ChannelManager>(pool, guild_id)
+                .await;
+        }
+    }
+
+    async fn active_channels<Db: Database, ChannelManager: VoiceChannelManager<Db>>(
+        &self,
+        pool: &Pool<Db>,
+        guild_id: GuildId,
+    ) {
+        let Some(recorder) = &self.0 else {
+            return;
+        };
+
+        if let Ok(count) = ChannelManager::count_channels(pool, guild_id).await {
+            recorder.set_gauge(ACTIVE_CHANNELS, &guild_labels(guild_id), count as f64);
+        }
+    }
+
+    pub(crate) fn command(&self, name: &str) {
+        if let Some(recorder) = &self.0 {
+            recorder.increment_counter(COMMAND_INVOCATIONS, &[("subcommand", name.to_string())]);
+        }
+    }
+
+    pub(crate) fn error(&self, error: &Error) {
+        if let Some(recorder) = &self.0 {
+            recorder.increment_counter(ERRORS, &[("variant", error.variant_name().to_string())]);
+        }
+    }
+
+    /// Counts the result's error, if it has one, and passes it through.
+    pub(crate) async fn track<T>(ctx: &Context, result: Result<T>) -> Result<T> {
+        if let Err(e) = &result {
+            Self::get(ctx).await.error(e);
+        }
+
+        result
+    }
+}
+
+/// Times a Discord API call, recording it under `endpoint`.
+pub(crate) async fn timed<T>(
+    ctx: &Context,
+    endpoint: &'static str,
+    request: impl Future<Output = T>,
+) -> T {
+    let start = Instant::now();
+    let result = request.await;
+
+    if let Some(recorder) = Metrics::get(ctx).await.0 {
+        recorder.record_histogram(
+            API_LATENCY,
+            &[("endpoint", endpoint.to_string())],
+            start.elapsed().as_secs_f64(),
+        );
+    }
+
+    result
+}
+
+fn guild_labels(guild_id: GuildId) -> [(&'static str, String); 1] {
+    [("guild_id", guild_id.to_string())]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counters_are_keyed_by_name_and_labels() {
+        let recorder = InMemoryRecorder::default();
+        let guild = guild_labels(GuildId::new(1));
+
+        recorder.increment_counter(CHANNELS_CREATED, &guild);
+        recorder.increment_counter(CHANNELS_CREATED, &guild);
+        recorder.increment_counter(CHANNELS_DELETED, &guild);
+
+        assert_eq!(recorder.counter(CHANNELS_CREATED, &guild), 2);
+        assert_eq!(recorder.counter(CHANNELS_DELETED, &guild), 1);
+        assert_eq!(
+            recorder.counter(CHANNELS_CREATED, &guild_labels(GuildId::new(2))),
+            0
+        );
+    }
+
+    #[test]
+    fn gauges_keep_the_latest_value() {
+        let recorder = InMemoryRecorder::default();
+        let guild = guild_labels(GuildId::new(1));
+
+        assert_eq!(recorder.gauge(ACTIVE_CHANNELS, &guild), None);
+
+        recorder.set_gauge(ACTIVE_CHANNELS, &guild, 3.0);
+        recorder.set_gauge(ACTIVE_CHANNELS, &guild, 2.0);
+
+        assert_eq!(recorder.gauge(ACTIVE_CHANNELS, &guild), Some(2.0));
+    }
+
+    #[test]
+    fn histograms_keep_every_value() {
+        let recorder = InMemoryRecorder::default();
+        let labels = [("endpoint", String::from("create_channel"))];
+
+        recorder.record_histogram(API_LATENCY, &labels, 0.5);
+        recorder.record_histogram(API_LATENCY, &labels, 0.25);
+
+        assert_eq!(recorder.histogram(API_LATENCY, &labels), vec![0.5, 0.25]);
+        assert!(
+            recorder
+                .histogram(API_LATENCY, &[("endpoint", String::from("move_member"))])
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn commands_are_labelled_by_subcommand() {
+        let recorder = Arc::new(InMemoryRecorder::default());
+        let metrics = Metrics(Some(recorder.clone()));
+
+        metrics.command("create");
+
+        assert_eq!(
+            recorder.counter(
+                COMMAND_INVOCATIONS,
+                &[("subcommand", String::from("create"))]
+            ),
+            1
+        );
+    }
+
+    #[test]
+    fn errors_are_labelled_by_variant() {
+        let recorder = Arc::new(InMemoryRecorder::default());
+        let metrics = Metrics(Some(recorder.clone()));
+
+        metrics.error(&Error::NotSetup);
+        metrics.error(&Error::MaxChannels(1));
+        metrics.error(&Error::MaxChannels(2));
+
+        assert_eq!(
+            recorder.counter(ERRORS, &[("variant", String::from("NotSetup"))]),
+            1
+        );
+        assert_eq!(
+            recorder.counter(ERRORS, &[("variant", String::from("MaxChannels"))]),
+            2
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use regex::{Regex, RegexBuilder};
+use serenity::all::{Context, GuildId};
+use serenity::prelude::TypeMapKey;
+use tracing::warn;
+
+use crate::{Error, Result, TempVoiceRow};
+
+pub enum NameCheck {
+    Allow,
+    Replace(String),
+    Reject,
+}
+
+/// Lets the hosting bot run its own moderation over channel names before the
+/// guild's blocklist and pattern are applied.
+///
+/// Register an implementation in the `TypeMap` under [`NameFilterHook`].
+#[async_trait]
+pub trait NameFilter: Send + Sync {
+    async fn check(&self, ctx: &Context, guild_id: GuildId, name: &str) -> NameCheck;
+}
+
+pub struct NameFilterHook;
+
+impl TypeMapKey for NameFilterHook {
+    type Value = Arc<dyn NameFilter>;
+}
+
+/// Validates a name the user typed themselves, rejecting it outright if it
+/// breaks the filter.
+pub(crate) async fn filter_name(
+    ctx: &Context,
+    guild_data: Option<&TempVoiceRow>,
+    guild_id: GuildId,
+    name: String,
+) -> Result<String> {
+    let name = match run_hook(ctx, guild_id, &name).await {
+        NameCheck::Allow => name,
+        NameCheck::Replace(name) => name,
+        NameCheck::Reject => return Err(Error::InvalidChannelName),
+    };
+
+    match guild_data.map(build_filter) {
+        Some(filter) if filter.is_match(&name) => Err(Error::InvalidChannelName),
+        _ => Ok(name),
+    }
+}
+
+/// Cleans up a generated name, such as one built from a display name, by
+/// masking anything the filter matches. Falls back to `fallback` if the hook
+/// rejects the name entirely.
+pub(crate) async fn sanitise_name(
+    ctx: &Context,
+    guild_data: Option<&TempVoiceRow>,
+    guild_id: GuildId,
+    name: String,
+    fallback: &str,
+) -> String {
+    let name = match run_hook(ctx, guild_id, &name).await {
+        NameCheck::Allow => name,
+        NameCheck::Replace(name) => name,
+        NameCheck::Reject => return fallback.to_string(),
+    };
+
+    match guild_data.map(build_filter) {
+        Some(filter) => filter.mask(name),
+        None => name,
+    }
+}
+
+async fn run_hook(ctx: &Context, guild_id: GuildId, name: &str) -> NameCheck {
+    let hook = ctx.data.read().await.get::<NameFilterHook>().cloned();
+
+    match hook {
+        Some(hook) => hook.check(ctx, guild_id, name).await,
+        None => NameCheck::Allow,
+    }
+}
+
+fn build_filter(guild_data: &TempVoiceRow) -> Filter {
+    Filter::new(guild_data.name_blocklist(), guild_data.name_pattern())
+}
+
+/// Compiles a name pattern the same way the filter does, so `/voice admin
+/// filter` can reject a pattern before it's saved.
+pub(crate) fn compile_pattern(pattern: &str) -> std::result::Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern).case_insensitive(true).build()
+}
+
+/// The guild's blocked words and pattern, ignoring case. They're compiled
+/// separately so a pattern that no longer compiles can't switch off word
+/// blocking.
+struct Filter {
+    words: Option<Regex>,
+    pattern: Option<Regex>,
+}
+
+impl Filter {
+    fn new(blocklist: &[String], pattern: Option<&str>) -> Self {
+        let words = match blocklist {
+            [] => None,
+            words => {
+                let words = words
+                    .iter()
+                    .map(|word| regex::escape(word))
+                    .collect::<Vec<_>>()
+                    .join("|");
+
+                compile_pattern(&words).ok()
+            }
+        };
+
+        let pattern = pattern.and_then(|pattern| match compile_pattern(pattern) {
+            Ok(pattern) => Some(pattern),
+            Err(e) => {
+                warn!(error = %e, "ignoring invalid name pattern");
+                None
+            }
+        });
+
+        Self { words, pattern }
+    }
+
+    fn regexes(&self) -> impl Iterator<Item = &Regex> {
+        self.words.iter().chain(&self.pattern)
+    }
+
+    fn is_match(&self, name: &str) -> bool {
+        self.regexes().any(|regex| regex.is_match(name))
+    }
+
+    /// Replaces every character the filter matches with `*`.
+    fn mask(&self, name: String) -> String {
+        self.regexes().fold(name, |name, regex| {
+            regex
+                .replace_all(&name, |caps: &regex::Captures| {
+                    "*".repeat(caps[0].chars().count())
+                })
+                .into_owned()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocklist(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn nothing_matches_without_words_or_pattern() {
+        let filter = Filter::new(&[], None);
+
+        assert!(!filter.is_match("anything"));
+        assert_eq!(filter.mask(String::from("anything")), "anything");
+    }
+
+    #[test]
+    fn blocked_words_match_ignoring_case() {
+        let filter = Filter::new(&blocklist(&["bad"]), None);
+
+        assert!(filter.is_match("A BAD name"));
+        assert!(!filter.is_match("A good name"));
+    }
+
+    #[test]
+    fn blocked_words_are_matched_literally() {
+        let filter = Filter::new(&blocklist(&["a.b"]), None);
+
+        assert!(filter.is_match("a.b"));
+        assert!(!filter.is_match("axb"));
+    }
+
+    #[test]
+    fn pattern_is_checked_alongside_blocked_words() {
+        let filter = Filter::new(&blocklist(&["bad"]), Some("^x+$"));
+
+        assert!(filter.is_match("XXX"));
+        assert!(filter.is_match("bad"));
+        assert!(!filter.is_match("xa"));
+    }
+
+    #[test]
+    fn invalid_pattern_keeps_word_blocking() {
+        let filter = Filter::new(&blocklist(&["bad"]), Some("("));
+
+        assert!(filter.is_match("bad"));
+        assert!(!filter.is_match("("));
+    }
+
+    #[test]
+    fn invalid_patterns_are_rejected() {
+        assert!(compile_pattern("(").is_err());
+        assert!(compile_pattern("^x+$").is_ok());
+    }
+
+    #[test]
+    fn mask_replaces_each_matched_character() {
+        let filter = Filter::new(&blocklist(&["bad", "ñé"]), Some("[0-9]+"));
+
+        assert_eq!(
+            filter.mask(String::from("Bad ñé room 42")),
+            "*** ** room **"
+        );
+    }
+}
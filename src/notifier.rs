@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serenity::all::{
+    ChannelId, CommandInteraction, Context, CreateInteractionResponseFollowup, CreateMessage,
+    Mentionable, UserId,
+};
+use serenity::prelude::TypeMapKey;
+
+/// Where a notification was delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delivery {
+    DirectMessage,
+    Channel,
+    FollowUp,
+    Failed,
+}
+
+pub struct Notification<'a> {
+    pub user_id: UserId,
+    pub content: String,
+    /// The voice channel whose text chat can be used instead of a DM.
+    pub channel_id: Option<ChannelId>,
+    /// The interaction to follow up on if the notification is for the user
+    /// who ran the command.
+    pub interaction: Option<&'a CommandInteraction>,
+}
+
+impl<'a> Notification<'a> {
+    pub fn new(user_id: impl Into<UserId>, content: impl Into<String>) -> Self {
+        Self {
+            user_id: user_id.into(),
+            content: content.into(),
+            channel_id: None,
+            interaction: None,
+        }
+    }
+
+    pub fn channel(mut self, id: impl Into<ChannelId>) -> Self {
+        self.channel_id = Some(id.into());
+        self
+    }
+
+    pub fn interaction(mut self, interaction: &'a CommandInteraction) -> Self {
+        self.interaction = Some(interaction);
+        self
+    }
+}
+
+/// Delivers messages meant for a single user, such as invites and expiry
+/// warnings.
+///
+/// [`DefaultNotifier`] is used unless an implementation is registered in the
+/// `TypeMap` under [`NotifierHook`].
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(&self, ctx: &Context, notification: &Notification<'_>) -> Delivery;
+}
+
+pub struct NotifierHook;
+
+impl TypeMapKey for NotifierHook {
+    type Value = Arc<dyn Notifier>;
+}
+
+/// Tries a DM first, then mentions the user in the voice channel's text chat,
+/// then sends an ephemeral follow-up to their interaction.
+pub struct DefaultNotifier;
+
+#[async_trait]
+impl Notifier for DefaultNotifier {
+    async fn notify(&self, ctx: &Context, notification: &Notification<'_>) -> Delivery {
+        let dm = notification
+            .user_id
+            .direct_message(
+                ctx,
+                CreateMessage::new().content(notification.content.as_str()),
+            )
+            .await;
+
+        if dm.is_ok() {
+            return Delivery::DirectMessage;
+        }
+
+        if let Some(channel_id) = notification.channel_id {
+            let content = format!(
+                "{} {}",
+                notification.user_id.mention(),
+                notification.content
+            );
+
+            if channel_id
+                .send_message(ctx, CreateMessage::new().content(content))
+                .await
+                .is_ok()
+            {
+                return Delivery::Channel;
+            }
+        }
+
+        if let Some(interaction) = notification.interaction
+            && interaction.user.id == notification.user_id
+            && interaction
+                .create_followup(
+                    ctx,
+                    CreateInteractionResponseFollowup::new()
+                        .content(notification.content.as_str())
+                        .ephemeral(true),
+                )
+                .await
+                .is_ok()
+        {
+            return Delivery::FollowUp;
+        }
+
+        Delivery::Failed
+    }
+}
+
+pub(crate) async fn notify(ctx: &Context, notification: Notification<'_>) -> Delivery {
+    let hook = ctx.data.read().await.get::<NotifierHook>().cloned();
+
+    match hook {
+        Some(notifier) => notifier.notify(ctx, &notification).await,
+        None => DefaultNotifier.notify(ctx, &notification).await,
+    }
+}
@@ -0,0 +1,99 @@
+use std::cmp::Reverse;
+
+use serenity::all::{ChannelId, ChannelType, Context};
+use sqlx::{Database, Pool};
+use tracing::warn;
+
+use crate::category::guild_channels;
+use crate::{ChannelOrder, Result, TempVoiceRow, VoiceChannelManager, VoiceStateCache};
+
+/// Repositions the temporary channels in a category according to the guild's
+/// ordering rules. The creator channel always stays at the top and channels
+/// the module doesn't manage are left alone.
+///
+/// Ordering is cosmetic, so failures are logged rather than failing the
+/// create or delete that triggered it.
+pub(crate) async fn reorder_category<Db: Database, ChannelManager: VoiceChannelManager<Db>>(
+    ctx: &Context,
+    pool: &Pool<Db>,
+    guild_data: &TempVoiceRow,
+    category: ChannelId,
+) {
+    if let Err(e) = try_reorder::<Db, ChannelManager>(ctx, pool, guild_data, category).await {
+        warn!(error = ?e, %category, "failed to reorder category");
+    }
+}
+
+async fn try_reorder<Db: Database, ChannelManager: VoiceChannelManager<Db>>(
+    ctx: &Context,
+    pool: &Pool<Db>,
+    guild_data: &TempVoiceRow,
+    category: ChannelId,
+) -> Result<()> {
+    let order = guild_data.channel_order();
+
+    if order == ChannelOrder::Discord && !guild_data.pin_persistent() {
+        return Ok(());
+    }
+
+    let guild_id = guild_data.guild_id();
+    let creator_channel = guild_data.temp_voice_creator_channel;
+
+    let channels = guild_channels(ctx, guild_id)
+        .await?
+        .into_values()
+        .filter(|channel| channel.parent_id == Some(category) && channel.kind == ChannelType::Voice)
+        .collect::<Vec<_>>();
+
+    let (creator, channels): (Vec<_>, Vec<_>) = channels
+        .into_iter()
+        .partition(|channel| Some(channel.id.get() as i64) == creator_channel);
+
+    let cache = VoiceStateCache::get(ctx).await;
+    let mut keyed = Vec::with_capacity(channels.len());
+
+    for channel in channels {
+        let Some(row) = ChannelManager::get(pool, channel.id).await.unwrap() else {
+            continue;
+        };
+
+        let pinned = guild_data.pin_persistent() && row.is_persistent();
+        let members = cache.member_count(guild_id, channel.id);
+
+        keyed.push((Reverse(pinned), members, channel));
+    }
+
+    let Some(base) = creator
+        .iter()
+        .chain(keyed.iter().map(|(_, _, channel)| channel))
+        .map(|channel| channel.position)
+        .min()
+    else {
+        return Ok(());
+    };
+
+    // Channel ids are snowflakes, so comparing them compares creation times.
+    match order {
+        ChannelOrder::Discord => {
+            keyed.sort_by_key(|(pinned, _, channel)| (*pinned, channel.position))
+        }
+        ChannelOrder::Creator => {
+            keyed.sort_by_key(|(pinned, _, channel)| (*pinned, Reverse(channel.id)))
+        }
+        ChannelOrder::Members => {
+            keyed.sort_by_key(|(pinned, members, channel)| (*pinned, Reverse(*members), channel.id))
+        }
+        ChannelOrder::Created => keyed.sort_by_key(|(pinned, _, channel)| (*pinned, channel.id)),
+    }
+
+    let positions = creator
+        .iter()
+        .map(|channel| channel.id)
+        .chain(keyed.iter().map(|(_, _, channel)| channel.id))
+        .zip(base as u64..)
+        .collect::<Vec<_>>();
+
+    guild_id.reorder_channels(ctx, positions).await?;
+
+    Ok(())
+}
@@ -0,0 +1,349 @@
+use std::collections::HashMap;
+
+use serenity::all::{
+    ChannelId, Context, EditChannel, GuildId, PermissionOverwrite, PermissionOverwriteType,
+    Permissions, ResolvedValue, RoleId, UserId,
+};
+
+use crate::{ChannelRestriction, Error, VoiceChannelMode, VoiceChannelRow};
+
+/// The channel's overwrites for the given mode and restrictions.
+///
+/// `/voice create`, `/voice privacy`, the control panel and the creator
+/// channel all go through here, so the same choice always produces the same
+/// overwrites. The `@everyone` overwrite is replaced outright and `speakers`
+/// can talk in spectator mode. Anyone else loses a `SPEAK` allow left over
+/// from an earlier promotion.
+pub(crate) fn privacy_overwrites(
+    perms: Vec<PermissionOverwrite>,
+    everyone: RoleId,
+    mode: VoiceChannelMode,
+    speakers: impl IntoIterator<Item = UserId>,
+    restrictions: &[ChannelRestriction],
+) -> Vec<PermissionOverwrite> {
+    let speakers = speakers.into_iter().collect::<Vec<_>>();
+
+    let mut perms = perms
+        .into_iter()
+        .filter(|perm| perm.kind != PermissionOverwriteType::Role(everyone))
+        .filter_map(|mut perm| {
+            if let PermissionOverwriteType::Member(user_id) = perm.kind
+                && !speakers.contains(&user_id)
+            {
+                perm.allow.remove(Permissions::SPEAK);
+
+                // Overwrites that only promoted the member have nothing left.
+                if perm.allow.is_empty() && perm.deny.is_empty() {
+                    return None;
+                }
+            }
+
+            Some(perm)
+        })
+        .collect::<Vec<_>>();
+
+    let (allow, mut deny) = match mode {
+        VoiceChannelMode::Open => (Permissions::VIEW_CHANNEL, Permissions::empty()),
+        VoiceChannelMode::Spectator => (Permissions::VIEW_CHANNEL, Permissions::SPEAK),
+        VoiceChannelMode::Locked => (Permissions::empty(), Permissions::CONNECT),
+        VoiceChannelMode::Invisible => (Permissions::empty(), Permissions::VIEW_CHANNEL),
+    };
+
+    for restriction in restrictions {
+        deny |= restriction.denied();
+    }
+
+    perms.push(PermissionOverwrite {
+        allow: allow - deny,
+        deny,
+        kind: PermissionOverwriteType::Role(everyone),
+    });
+
+    if mode == VoiceChannelMode::Spectator {
+        for user in speakers {
+            let perm = perms.iter_mut().find(|perm| {
+                matches!(perm.kind, PermissionOverwriteType::Member(user_id) if user_id == user)
+            });
+
+            match perm {
+                Some(perm) => perm.allow |= Permissions::SPEAK,
+                None => perms.push(PermissionOverwrite {
+                    allow: Permissions::SPEAK,
+                    deny: Permissions::empty(),
+                    kind: PermissionOverwriteType::Member(user),
+                }),
+            }
+        }
+    }
+
+    perms
+}
+
+/// The overwrites for a new channel owned by `owner_id`.
+pub(crate) fn new_channel_overwrites(
+    guild_id: GuildId,
+    owner_id: UserId,
+    mode: VoiceChannelMode,
+    restrictions: &[ChannelRestriction],
+) -> Vec<PermissionOverwrite> {
+    let owner = PermissionOverwrite {
+        allow: Permissions::all(),
+        deny: Permissions::empty(),
+        kind: PermissionOverwriteType::Member(owner_id),
+    };
+
+    privacy_overwrites(
+        vec![owner],
+        guild_id.everyone_role(),
+        mode,
+        [owner_id],
+        restrictions,
+    )
+}
+
+/// Updates the channel's overwrites to match the row's mode and restrictions,
+/// switching to a new mode first if one is given. The caller saves the row.
+pub(crate) async fn apply_privacy(
+    ctx: &Context,
+    guild_id: GuildId,
+    row: &mut VoiceChannelRow,
+    mode: Option<VoiceChannelMode>,
+) -> Result<(), Error> {
+    let channel_id = row.channel_id();
+
+    let channel = channel_id
+        .to_channel(ctx)
+        .await?
+        .guild()
+        .ok_or(Error::ChannelNotFound(channel_id))?;
+
+    if let Some(mode) = mode {
+        row.set_mode(mode);
+    }
+
+    let speakers = row
+        .trusted_ids()
+        .into_iter()
+        .chain(row.speakers())
+        .chain([row.owner_id()]);
+
+    let perms = privacy_overwrites(
+        channel.permission_overwrites,
+        guild_id.everyone_role(),
+        row.mode(),
+        speakers,
+        &row.restrictions(),
+    );
+
+    channel_id
+        .edit(ctx, EditChannel::new().permissions(perms))
+        .await?;
+
+    Ok(())
+}
+
+/// Reads the restriction options shared by every privacy command, leaving out
+/// any that weren't given.
+pub(crate) fn restriction_options(
+    options: &mut HashMap<&str, ResolvedValue<'_>>,
+) -> Vec<(ChannelRestriction, bool)> {
+    ChannelRestriction::ALL
+        .into_iter()
+        .filter_map(|restriction| match options.remove(restriction.as_str()) {
+            Some(ResolvedValue::Boolean(enabled)) => Some((restriction, enabled)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Lets a user speak in a spectator channel, keeping the rest of their
+/// overwrite.
+pub(crate) async fn grant_speak(
+    ctx: &Context,
+    channel_id: ChannelId,
+    user_id: UserId,
+) -> Result<(), Error> {
+    let channel = channel_id.to_channel(ctx).await?.guild().unwrap();
+
+    let mut perm = channel
+        .permission_overwrites
+        .into_iter()
+        .find(|perm| perm.kind == PermissionOverwriteType::Member(user_id))
+        .unwrap_or(PermissionOverwrite {
+            allow: Permissions::empty(),
+            deny: Permissions::empty(),
+            kind: PermissionOverwriteType::Member(user_id),
+        });
+
+    if perm.allow.contains(Permissions::SPEAK) {
+        return Ok(());
+    }
+
+    perm.allow |= Permissions::SPEAK;
+    perm.deny.remove(Permissions::SPEAK);
+
+    channel_id.create_permission(ctx, perm).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EVERYONE: RoleId = RoleId::new(1);
+    const OWNER: UserId = UserId::new(2);
+    const SPEAKER: UserId = UserId::new(3);
+
+    fn everyone(perms: &[PermissionOverwrite]) -> &PermissionOverwrite {
+        let mut everyone = perms
+            .iter()
+            .filter(|perm| perm.kind == PermissionOverwriteType::Role(EVERYONE));
+
+        let perm = everyone.next().unwrap();
+        assert!(everyone.next().is_none());
+
+        perm
+    }
+
+    fn member(perms: &[PermissionOverwrite], user_id: UserId) -> Option<&PermissionOverwrite> {
+        perms
+            .iter()
+            .find(|perm| perm.kind == PermissionOverwriteType::Member(user_id))
+    }
+
+    #[test]
+    fn modes_set_the_everyone_overwrite() {
+        let cases = [
+            (
+                VoiceChannelMode::Open,
+                Permissions::VIEW_CHANNEL,
+                Permissions::empty(),
+            ),
+            (
+                VoiceChannelMode::Spectator,
+                Permissions::VIEW_CHANNEL,
+                Permissions::SPEAK,
+            ),
+            (
+                VoiceChannelMode::Locked,
+                Permissions::empty(),
+                Permissions::CONNECT,
+            ),
+            (
+                VoiceChannelMode::Invisible,
+                Permissions::empty(),
+                Permissions::VIEW_CHANNEL,
+            ),
+        ];
+
+        for (mode, allow, deny) in cases {
+            let perms = privacy_overwrites(Vec::new(), EVERYONE, mode, [], &[]);
+
+            assert_eq!(everyone(&perms).allow, allow);
+            assert_eq!(everyone(&perms).deny, deny);
+        }
+    }
+
+    #[test]
+    fn replaces_the_existing_everyone_overwrite() {
+        let existing = vec![PermissionOverwrite {
+            allow: Permissions::empty(),
+            deny: Permissions::CONNECT,
+            kind: PermissionOverwriteType::Role(EVERYONE),
+        }];
+
+        let perms = privacy_overwrites(existing, EVERYONE, VoiceChannelMode::Open, [], &[]);
+
+        assert_eq!(everyone(&perms).deny, Permissions::empty());
+    }
+
+    #[test]
+    fn restrictions_are_added_to_the_mode() {
+        let perms = privacy_overwrites(
+            Vec::new(),
+            EVERYONE,
+            VoiceChannelMode::Locked,
+            [],
+            &[
+                ChannelRestriction::PushToTalk,
+                ChannelRestriction::TextLocked,
+            ],
+        );
+
+        assert_eq!(
+            everyone(&perms).deny,
+            Permissions::CONNECT | Permissions::USE_VAD | Permissions::SEND_MESSAGES
+        );
+    }
+
+    #[test]
+    fn speakers_can_talk_in_spectator_mode() {
+        let perms = new_channel_overwrites(
+            GuildId::new(EVERYONE.get()),
+            OWNER,
+            VoiceChannelMode::Spectator,
+            &[],
+        );
+        let perms = privacy_overwrites(
+            perms,
+            EVERYONE,
+            VoiceChannelMode::Spectator,
+            [OWNER, SPEAKER],
+            &[],
+        );
+
+        assert_eq!(member(&perms, OWNER).unwrap().allow, Permissions::all());
+        assert_eq!(member(&perms, SPEAKER).unwrap().allow, Permissions::SPEAK);
+    }
+
+    #[test]
+    fn former_speakers_lose_their_voice() {
+        let existing = vec![
+            PermissionOverwrite {
+                allow: Permissions::SPEAK,
+                deny: Permissions::empty(),
+                kind: PermissionOverwriteType::Member(SPEAKER),
+            },
+            PermissionOverwrite {
+                allow: Permissions::SPEAK | Permissions::CONNECT,
+                deny: Permissions::empty(),
+                kind: PermissionOverwriteType::Member(UserId::new(4)),
+            },
+        ];
+
+        for mode in [VoiceChannelMode::Open, VoiceChannelMode::Spectator] {
+            let perms = privacy_overwrites(existing.clone(), EVERYONE, mode, [OWNER], &[]);
+
+            assert!(member(&perms, SPEAKER).is_none());
+            assert_eq!(
+                member(&perms, UserId::new(4)).unwrap().allow,
+                Permissions::CONNECT
+            );
+        }
+    }
+
+    #[test]
+    fn speakers_are_ignored_outside_spectator_mode() {
+        let perms =
+            privacy_overwrites(Vec::new(), EVERYONE, VoiceChannelMode::Open, [SPEAKER], &[]);
+
+        assert!(member(&perms, SPEAKER).is_none());
+    }
+
+    #[test]
+    fn new_channels_keep_the_owner_overwrite() {
+        let perms = new_channel_overwrites(
+            GuildId::new(EVERYONE.get()),
+            OWNER,
+            VoiceChannelMode::Invisible,
+            &[ChannelRestriction::NoVideo],
+        );
+
+        assert_eq!(member(&perms, OWNER).unwrap().allow, Permissions::all());
+        assert_eq!(
+            everyone(&perms).deny,
+            Permissions::VIEW_CHANNEL | Permissions::STREAM
+        );
+    }
+}
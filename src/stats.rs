@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use serenity::all::{ChannelId, Context, GuildId, Timestamp, UserId};
+use serenity::prelude::TypeMapKey;
+use sqlx::any::AnyQueryResult;
+use sqlx::prelude::FromRow;
+use sqlx::{Database, Pool};
+
+use crate::Result;
+
+#[async_trait]
+pub trait VoiceStatsManager<Db: Database> {
+    async fn get_session(pool: &Pool<Db>, id: ChannelId) -> sqlx::Result<Option<ChannelSession>>;
+    async fn save_session(pool: &Pool<Db>, session: ChannelSession)
+    -> sqlx::Result<AnyQueryResult>;
+    async fn get_user(
+        pool: &Pool<Db>,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> sqlx::Result<Option<UserStats>>;
+    async fn save_user(pool: &Pool<Db>, stats: UserStats) -> sqlx::Result<AnyQueryResult>;
+    /// The guild's users with the most voice time, most first.
+    async fn leaderboard(
+        pool: &Pool<Db>,
+        guild_id: GuildId,
+        limit: i64,
+    ) -> sqlx::Result<Vec<UserStats>>;
+    async fn delete_guild(pool: &Pool<Db>, guild_id: GuildId) -> sqlx::Result<AnyQueryResult>;
+}
+
+/// The lifetime of a single temporary channel.
+#[derive(FromRow)]
+pub struct ChannelSession {
+    pub id: i64,
+    pub guild_id: i64,
+    pub owner_id: i64,
+    pub created_at: i64,
+    pub deleted_at: Option<i64>,
+    pub peak_members: i64,
+    /// Seconds spent in the channel, summed over every member.
+    pub total_seconds: i64,
+}
+
+impl ChannelSession {
+    pub fn new(
+        id: impl Into<ChannelId>,
+        guild_id: impl Into<GuildId>,
+        owner_id: impl Into<UserId>,
+    ) -> Self {
+        let id = id.into();
+
+        Self {
+            id: id.get() as i64,
+            guild_id: guild_id.into().get() as i64,
+            owner_id: owner_id.into().get() as i64,
+            created_at: id.created_at().unix_timestamp(),
+            deleted_at: None,
+            peak_members: 0,
+            total_seconds: 0,
+        }
+    }
+
+    pub fn channel_id(&self) -> ChannelId {
+        ChannelId::new(self.id as u64)
+    }
+
+    pub fn total_minutes(&self) -> i64 {
+        self.total_seconds / 60
+    }
+
+    pub fn record_members(&mut self, members: usize) {
+        self.peak_members = self.peak_members.max(members as i64);
+    }
+
+    pub fn end(&mut self) {
+        self.deleted_at
+            .get_or_insert(Timestamp::now().unix_timestamp());
+    }
+
+    pub async fn save<Db: Database, Manager: VoiceStatsManager<Db>>(
+        self,
+        pool: &Pool<Db>,
+    ) -> Result<()> {
+        Manager::save_session(pool, self).await.unwrap();
+
+        Ok(())
+    }
+}
+
+/// A user's time in a guild's temporary channels.
+#[derive(FromRow)]
+pub struct UserStats {
+    pub guild_id: i64,
+    pub user_id: i64,
+    pub sessions: i64,
+    pub total_seconds: i64,
+}
+
+impl UserStats {
+    pub fn new(guild_id: impl Into<GuildId>, user_id: impl Into<UserId>) -> Self {
+        Self {
+            guild_id: guild_id.into().get() as i64,
+            user_id: user_id.into().get() as i64,
+            sessions: 0,
+            total_seconds: 0,
+        }
+    }
+
+    pub fn user_id(&self) -> UserId {
+        UserId::new(self.user_id as u64)
+    }
+
+    pub fn total_minutes(&self) -> i64 {
+        self.total_seconds / 60
+    }
+
+    pub fn add_session(&mut self, seconds: i64) {
+        self.sessions += 1;
+        self.total_seconds += seconds;
+    }
+
+    pub async fn save<Db: Database, Manager: VoiceStatsManager<Db>>(
+        self,
+        pool: &Pool<Db>,
+    ) -> Result<()> {
+        Manager::save_user(pool, self).await.unwrap();
+
+        Ok(())
+    }
+}
+
+/// When each user joined their current channel. Time spent before a restart
+/// isn't recorded.
+#[derive(Default)]
+pub(crate) struct VoiceSessions {
+    joined: Mutex<HashMap<(GuildId, UserId), (ChannelId, i64)>>,
+}
+
+impl VoiceSessions {
+    pub(crate) async fn get(ctx: &Context) -> Arc<Self> {
+        if let Some(sessions) = ctx.data.read().await.get::<Self>() {
+            return Arc::clone(sessions);
+        }
+
+        let mut data = ctx.data.write().await;
+        Arc::clone(data.entry::<Self>().or_default())
+    }
+
+    pub(crate) fn join(&self, guild_id: GuildId, user_id: UserId, channel_id: ChannelId) {
+        self.joined.lock().unwrap().insert(
+            (guild_id, user_id),
+            (channel_id, Timestamp::now().unix_timestamp()),
+        );
+    }
+
+    /// Returns the channel the user left and how many seconds they were in
+    /// it.
+    pub(crate) fn leave(&self, guild_id: GuildId, user_id: UserId) -> Option<(ChannelId, i64)> {
+        self.joined
+            .lock()
+            .unwrap()
+            .remove(&(guild_id, user_id))
+            .map(|(channel_id, joined_at)| {
+                (channel_id, Timestamp::now().unix_timestamp() - joined_at)
+            })
+    }
+}
+
+impl TypeMapKey for VoiceSessions {
+    type Value = Arc<VoiceSessions>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leave_returns_the_joined_channel_once() {
+        let sessions = VoiceSessions::default();
+        let (guild, user) = (GuildId::new(1), UserId::new(1));
+
+        sessions.join(guild, user, ChannelId::new(10));
+
+        let (channel_id, seconds) = sessions.leave(guild, user).unwrap();
+        assert_eq!(channel_id, ChannelId::new(10));
+        assert!(seconds >= 0);
+
+        assert!(sessions.leave(guild, user).is_none());
+    }
+
+    #[test]
+    fn joining_again_replaces_the_channel() {
+        let sessions = VoiceSessions::default();
+        let (guild, user) = (GuildId::new(1), UserId::new(1));
+
+        sessions.join(guild, user, ChannelId::new(10));
+        sessions.join(guild, user, ChannelId::new(20));
+
+        assert_eq!(
+            sessions
+                .leave(guild, user)
+                .map(|(channel_id, _)| channel_id),
+            Some(ChannelId::new(20))
+        );
+    }
+
+    #[test]
+    fn sessions_are_kept_per_guild() {
+        let sessions = VoiceSessions::default();
+        let user = UserId::new(1);
+
+        sessions.join(GuildId::new(1), user, ChannelId::new(10));
+
+        assert!(sessions.leave(GuildId::new(2), user).is_none());
+        assert!(sessions.leave(GuildId::new(1), user).is_some());
+    }
+
+    #[test]
+    fn record_members_keeps_the_peak() {
+        let mut session = ChannelSession::new(ChannelId::new(10), GuildId::new(1), UserId::new(1));
+
+        session.record_members(3);
+        session.record_members(5);
+        session.record_members(2);
+
+        assert_eq!(session.peak_members, 5);
+    }
+
+    #[test]
+    fn end_keeps_the_first_deletion_time() {
+        let mut session = ChannelSession::new(ChannelId::new(10), GuildId::new(1), UserId::new(1));
+        session.deleted_at = Some(100);
+
+        session.end();
+
+        assert_eq!(session.deleted_at, Some(100));
+    }
+
+    #[test]
+    fn add_session_counts_sessions_and_time() {
+        let mut stats = UserStats::new(GuildId::new(1), UserId::new(1));
+
+        stats.add_session(90);
+        stats.add_session(60);
+
+        assert_eq!(stats.sessions, 2);
+        assert_eq!(stats.total_minutes(), 2);
+    }
+}
@@ -1,7 +1,10 @@
 use std::collections::HashSet;
 
 use async_trait::async_trait;
-use serenity::all::{ChannelId, UserId};
+use serde::{Deserialize, Serialize};
+use serenity::all::{
+    ChannelId, GuildChannel, GuildId, PermissionOverwrite, Permissions, Timestamp, UserId,
+};
 use sqlx::any::AnyQueryResult;
 use sqlx::prelude::FromRow;
 use sqlx::{Database, Pool};
@@ -11,32 +14,73 @@ use crate::Result;
 #[async_trait]
 pub trait VoiceChannelManager<Db: Database> {
     async fn get(pool: &Pool<Db>, id: ChannelId) -> sqlx::Result<Option<VoiceChannelRow>>;
-    async fn count_persistent_channels(pool: &Pool<Db>, user_id: UserId) -> sqlx::Result<i64>;
+    async fn get_by_user(
+        pool: &Pool<Db>,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> sqlx::Result<Vec<VoiceChannelRow>>;
+    async fn get_persistent(
+        pool: &Pool<Db>,
+        guild_id: GuildId,
+    ) -> sqlx::Result<Vec<VoiceChannelRow>>;
+    /// Counts the user's persistent channels in the guild, hibernated ones
+    /// included.
+    async fn count_persistent_channels(
+        pool: &Pool<Db>,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> sqlx::Result<i64>;
+    /// Counts the guild's channels, leaving out hibernated ones.
+    async fn count_channels(pool: &Pool<Db>, guild_id: GuildId) -> sqlx::Result<i64>;
+    /// Counts the user's channels in the guild, leaving out hibernated ones.
+    async fn count_owned_channels(
+        pool: &Pool<Db>,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> sqlx::Result<i64>;
     async fn save(pool: &Pool<Db>, row: VoiceChannelRow) -> sqlx::Result<AnyQueryResult>;
     async fn delete(pool: &Pool<Db>, id: ChannelId) -> sqlx::Result<AnyQueryResult>;
+    async fn delete_guild(pool: &Pool<Db>, guild_id: GuildId) -> sqlx::Result<AnyQueryResult>;
 }
 
 #[derive(FromRow)]
 pub struct VoiceChannelRow {
     pub id: i64,
+    pub guild_id: i64,
     pub owner_id: i64,
     pub trusted_ids: Vec<i64>,
     pub invites: Vec<i64>,
     pub password: Option<String>,
     pub persistent: bool,
     pub mode: VoiceChannelMode,
+    /// Users promoted to speak while the channel is in spectator mode.
+    pub speakers: Vec<i64>,
+    pub restrictions: Vec<String>,
+    pub snapshot: Option<String>,
+    pub last_active: i64,
+    pub expiry_warned: bool,
 }
 
 impl VoiceChannelRow {
-    pub fn new(id: impl Into<ChannelId>, owner_id: impl Into<UserId>) -> Self {
+    pub fn new(
+        id: impl Into<ChannelId>,
+        guild_id: impl Into<GuildId>,
+        owner_id: impl Into<UserId>,
+    ) -> Self {
         Self {
             id: id.into().get() as i64,
+            guild_id: guild_id.into().get() as i64,
             owner_id: owner_id.into().get() as i64,
             trusted_ids: Vec::new(),
             invites: Vec::new(),
             password: None,
             persistent: false,
             mode: VoiceChannelMode::Open,
+            speakers: Vec::new(),
+            restrictions: Vec::new(),
+            snapshot: None,
+            last_active: Timestamp::now().unix_timestamp(),
+            expiry_warned: false,
         }
     }
 
@@ -44,6 +88,10 @@ impl VoiceChannelRow {
         ChannelId::new(self.id as u64)
     }
 
+    pub fn guild_id(&self) -> GuildId {
+        GuildId::new(self.guild_id as u64)
+    }
+
     pub fn owner_id(&self) -> UserId {
         UserId::new(self.owner_id as u64)
     }
@@ -76,6 +124,61 @@ impl VoiceChannelRow {
         self.trusted_ids().contains(&user_id) || self.owner_id() == user_id
     }
 
+    pub fn mode(&self) -> VoiceChannelMode {
+        self.mode
+    }
+
+    /// Promotions only last as long as the mode they were made in.
+    pub fn set_mode(&mut self, mode: VoiceChannelMode) {
+        self.mode = mode;
+        self.speakers.clear();
+    }
+
+    pub fn restrictions(&self) -> Vec<ChannelRestriction> {
+        self.restrictions
+            .iter()
+            .filter_map(|name| ChannelRestriction::from_name(name))
+            .collect()
+    }
+
+    pub fn set_restrictions(&mut self, restrictions: &[ChannelRestriction]) {
+        self.restrictions = restrictions
+            .iter()
+            .map(|restriction| restriction.as_str().to_string())
+            .collect();
+    }
+
+    pub fn set_restriction(&mut self, restriction: ChannelRestriction, enabled: bool) {
+        self.restrictions
+            .retain(|name| name != restriction.as_str());
+
+        if enabled {
+            self.restrictions.push(restriction.as_str().to_string());
+        }
+    }
+
+    pub fn speakers(&self) -> HashSet<UserId> {
+        self.speakers
+            .iter()
+            .map(|id| UserId::new(*id as u64))
+            .collect()
+    }
+
+    pub fn promote(&mut self, id: impl Into<UserId>) {
+        let id = id.into().get() as i64;
+
+        if !self.speakers.contains(&id) {
+            self.speakers.push(id);
+        }
+    }
+
+    /// Whether the user may speak in spectator mode.
+    pub fn can_speak(&self, user_id: impl Into<UserId>) -> bool {
+        let user_id = user_id.into();
+
+        self.is_trusted(user_id) || self.speakers().contains(&user_id)
+    }
+
     pub fn verify_password(&self, pass: &str) -> bool {
         self.password.as_deref() == Some(pass)
     }
@@ -86,6 +189,40 @@ impl VoiceChannelRow {
 
     pub fn toggle_persist(&mut self) {
         self.persistent = !self.persistent;
+        self.touch();
+    }
+
+    /// Seconds since someone last joined or left the channel.
+    pub fn idle_for(&self) -> i64 {
+        Timestamp::now().unix_timestamp() - self.last_active
+    }
+
+    pub fn touch(&mut self) {
+        self.last_active = Timestamp::now().unix_timestamp();
+        self.expiry_warned = false;
+    }
+
+    /// Hibernated channels have had their Discord channel deleted while
+    /// idle, keeping a snapshot of its settings to recreate it from.
+    pub fn is_hibernated(&self) -> bool {
+        self.snapshot.is_some()
+    }
+
+    pub fn snapshot(&self) -> Option<serde_json::Result<ChannelSnapshot>> {
+        self.snapshot.as_deref().map(serde_json::from_str)
+    }
+
+    pub fn hibernate(&mut self, channel: &GuildChannel) {
+        let snapshot = ChannelSnapshot::from(channel);
+
+        self.snapshot = Some(serde_json::to_string(&snapshot).unwrap());
+    }
+
+    /// Moves the row over to the recreated channel.
+    pub fn restore(&mut self, id: impl Into<ChannelId>) {
+        self.id = id.into().get() as i64;
+        self.snapshot = None;
+        self.touch();
     }
 
     pub fn trust(&mut self, id: impl Into<UserId>) {
@@ -104,16 +241,22 @@ impl VoiceChannelRow {
     }
 
     pub fn block(&mut self, id: impl Into<UserId>) {
+        self.remove_user(id);
+    }
+
+    pub fn remove_user(&mut self, id: impl Into<UserId>) {
         let id = id.into();
 
         self.trusted_ids
             .retain(|trusted_id| *trusted_id != id.get() as i64);
         self.invites.retain(|invite| *invite != id.get() as i64);
+        self.speakers.retain(|speaker| *speaker != id.get() as i64);
     }
 
     pub fn reset(&mut self) {
         self.trusted_ids.clear();
         self.invites.clear();
+        self.speakers.clear();
         self.password = None;
     }
 
@@ -136,11 +279,173 @@ impl VoiceChannelRow {
     }
 }
 
-#[derive(sqlx::Type)]
+/// The settings of a hibernated channel needed to recreate it.
+#[derive(Serialize, Deserialize)]
+pub struct ChannelSnapshot {
+    pub name: String,
+    pub user_limit: Option<u32>,
+    pub bitrate: Option<u32>,
+    pub rtc_region: Option<String>,
+    pub permission_overwrites: Vec<PermissionOverwrite>,
+}
+
+impl From<&GuildChannel> for ChannelSnapshot {
+    fn from(channel: &GuildChannel) -> Self {
+        Self {
+            name: channel.name.clone(),
+            user_limit: channel.user_limit,
+            bitrate: channel.bitrate,
+            rtc_region: channel.rtc_region.clone(),
+            permission_overwrites: channel.permission_overwrites.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, sqlx::Type)]
 #[sqlx(rename_all = "lowercase")]
 pub enum VoiceChannelMode {
+    #[default]
     Open,
     Spectator,
     Locked,
     Invisible,
 }
+
+impl VoiceChannelMode {
+    /// Parses the value of a `privacy` command option. The old `unlock`,
+    /// `visible` and `open-mic` choices each undid a restriction, so stale
+    /// command registrations and panel buttons still open the channel.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let mode = match name {
+            "open" | "unlock" | "visible" | "open-mic" => Self::Open,
+            "spectator" => Self::Spectator,
+            "lock" => Self::Locked,
+            "invisible" => Self::Invisible,
+            _ => return None,
+        };
+
+        Some(mode)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Spectator => "spectator",
+            Self::Locked => "lock",
+            Self::Invisible => "invisible",
+        }
+    }
+}
+
+/// Limits on what `@everyone` can do in a channel, applied on top of its
+/// mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelRestriction {
+    PushToTalk,
+    NoVideo,
+    NoSoundboard,
+    TextLocked,
+}
+
+impl ChannelRestriction {
+    pub const ALL: [Self; 4] = [
+        Self::PushToTalk,
+        Self::NoVideo,
+        Self::NoSoundboard,
+        Self::TextLocked,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        let restriction = match name {
+            "push_to_talk" => Self::PushToTalk,
+            "no_video" => Self::NoVideo,
+            "no_soundboard" => Self::NoSoundboard,
+            "text_locked" => Self::TextLocked,
+            _ => return None,
+        };
+
+        Some(restriction)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PushToTalk => "push_to_talk",
+            Self::NoVideo => "no_video",
+            Self::NoSoundboard => "no_soundboard",
+            Self::TextLocked => "text_locked",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::PushToTalk => "Only allow push-to-talk.",
+            Self::NoVideo => "Stop members using their camera or streaming.",
+            Self::NoSoundboard => "Stop members playing soundboard sounds.",
+            Self::TextLocked => "Stop members sending messages in the channel's text chat.",
+        }
+    }
+
+    /// What `@everyone` is denied while the restriction is on.
+    pub fn denied(&self) -> Permissions {
+        match self {
+            Self::PushToTalk => Permissions::USE_VAD,
+            Self::NoVideo => Permissions::STREAM,
+            Self::NoSoundboard => Permissions::USE_SOUNDBOARD | Permissions::USE_EXTERNAL_SOUNDS,
+            Self::TextLocked => Permissions::SEND_MESSAGES,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mode_names_round_trip() {
+        for mode in [
+            VoiceChannelMode::Open,
+            VoiceChannelMode::Spectator,
+            VoiceChannelMode::Locked,
+            VoiceChannelMode::Invisible,
+        ] {
+            assert_eq!(VoiceChannelMode::from_name(mode.as_str()), Some(mode));
+        }
+
+        assert_eq!(VoiceChannelMode::from_name("unknown"), None);
+    }
+
+    #[test]
+    fn legacy_mode_names_open_the_channel() {
+        for name in ["unlock", "visible", "open-mic"] {
+            assert_eq!(
+                VoiceChannelMode::from_name(name),
+                Some(VoiceChannelMode::Open)
+            );
+        }
+    }
+
+    #[test]
+    fn restriction_names_round_trip() {
+        for restriction in ChannelRestriction::ALL {
+            assert_eq!(
+                ChannelRestriction::from_name(restriction.as_str()),
+                Some(restriction)
+            );
+        }
+
+        assert_eq!(ChannelRestriction::from_name("unknown"), None);
+    }
+
+    #[test]
+    fn restrictions_never_deny_joining_or_talking() {
+        for restriction in ChannelRestriction::ALL {
+            let denied = restriction.denied();
+
+            assert!(
+                !denied.intersects(
+                    Permissions::VIEW_CHANNEL | Permissions::CONNECT | Permissions::SPEAK
+                )
+            );
+        }
+    }
+}
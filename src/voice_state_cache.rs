@@ -0,0 +1,167 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
+
+use serenity::all::{ChannelId, Context, Guild, GuildId, UserId, VoiceState};
+use serenity::prelude::TypeMapKey;
+
+#[derive(Debug)]
+pub struct CachedState {
+    pub channel_id: Option<ChannelId>,
+    pub guild_id: GuildId,
+    pub user_id: UserId,
+}
+
+impl CachedState {
+    pub fn new(channel_id: Option<ChannelId>, guild_id: GuildId, user_id: UserId) -> Self {
+        Self {
+            channel_id,
+            guild_id,
+            user_id,
+        }
+    }
+}
+
+impl From<&VoiceState> for CachedState {
+    fn from(state: &VoiceState) -> Self {
+        Self {
+            channel_id: state.channel_id,
+            guild_id: state.guild_id.unwrap(),
+            user_id: state.user_id,
+        }
+    }
+}
+
+/// Voice states of every guild, sharded so that updates in one guild never
+/// block lookups in another.
+///
+/// The cache lives in the `TypeMap` behind an [`Arc`], so the global data lock
+/// is only held long enough to clone the handle.
+#[derive(Default)]
+pub struct VoiceStateCache {
+    guilds: RwLock<HashMap<GuildId, Arc<Mutex<GuildVoiceStates>>>>,
+}
+
+#[derive(Default)]
+struct GuildVoiceStates {
+    users: HashMap<UserId, ChannelId>,
+    channels: HashMap<ChannelId, HashSet<UserId>>,
+}
+
+impl GuildVoiceStates {
+    fn insert(&mut self, user_id: UserId, channel_id: ChannelId) -> Option<ChannelId> {
+        let old = self.remove(user_id);
+
+        self.users.insert(user_id, channel_id);
+        self.channels.entry(channel_id).or_default().insert(user_id);
+
+        old
+    }
+
+    fn remove(&mut self, user_id: UserId) -> Option<ChannelId> {
+        let old = self.users.remove(&user_id)?;
+
+        if let Entry::Occupied(mut members) = self.channels.entry(old) {
+            members.get_mut().remove(&user_id);
+
+            if members.get().is_empty() {
+                members.remove();
+            }
+        }
+
+        Some(old)
+    }
+}
+
+impl VoiceStateCache {
+    pub async fn get(ctx: &Context) -> Arc<Self> {
+        let data = ctx.data.read().await;
+
+        data.get::<Self>()
+            .cloned()
+            .expect("Expected VoiceStateCache in TypeMap")
+    }
+
+    pub fn update(&self, new: &VoiceState) -> Option<CachedState> {
+        let guild_id = new
+            .guild_id
+            .expect("Should be in a guild as voice channels are guild only");
+
+        let guild = self.guild(guild_id);
+        let mut guild = guild.lock().unwrap();
+
+        let old = match new.channel_id {
+            Some(channel_id) => guild.insert(new.user_id, channel_id),
+            None => guild.remove(new.user_id),
+        };
+
+        old.map(|channel_id| CachedState::new(Some(channel_id), guild_id, new.user_id))
+    }
+
+    pub fn insert_guild(&self, guild: &Guild) {
+        let mut states = GuildVoiceStates::default();
+
+        for (user_id, state) in &guild.voice_states {
+            if let Some(channel_id) = state.channel_id {
+                states.insert(*user_id, channel_id);
+            }
+        }
+
+        self.guilds
+            .write()
+            .unwrap()
+            .insert(guild.id, Arc::new(Mutex::new(states)));
+    }
+
+    pub fn remove_guild(&self, guild_id: GuildId) {
+        self.guilds.write().unwrap().remove(&guild_id);
+    }
+
+    pub fn remove_user(&self, guild_id: GuildId, user_id: UserId) -> Option<ChannelId> {
+        let guild = self.guilds.read().unwrap().get(&guild_id).cloned()?;
+        let mut guild = guild.lock().unwrap();
+
+        guild.remove(user_id)
+    }
+
+    pub fn user_channel(&self, guild_id: GuildId, user_id: UserId) -> Option<ChannelId> {
+        let guild = self.guilds.read().unwrap().get(&guild_id).cloned()?;
+        let guild = guild.lock().unwrap();
+
+        guild.users.get(&user_id).copied()
+    }
+
+    pub fn channel_members(&self, guild_id: GuildId, channel_id: ChannelId) -> Vec<UserId> {
+        let Some(guild) = self.guilds.read().unwrap().get(&guild_id).cloned() else {
+            return Vec::new();
+        };
+        let guild = guild.lock().unwrap();
+
+        guild
+            .channels
+            .get(&channel_id)
+            .map(|members| members.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn member_count(&self, guild_id: GuildId, channel_id: ChannelId) -> usize {
+        let Some(guild) = self.guilds.read().unwrap().get(&guild_id).cloned() else {
+            return 0;
+        };
+        let guild = guild.lock().unwrap();
+
+        guild.channels.get(&channel_id).map_or(0, HashSet::len)
+    }
+
+    fn guild(&self, guild_id: GuildId) -> Arc<Mutex<GuildVoiceStates>> {
+        if let Some(guild) = self.guilds.read().unwrap().get(&guild_id) {
+            return Arc::clone(guild);
+        }
+
+        Arc::clone(self.guilds.write().unwrap().entry(guild_id).or_default())
+    }
+}
+
+impl TypeMapKey for VoiceStateCache {
+    type Value = Arc<VoiceStateCache>;
+}
@@ -0,0 +1,49 @@
+use serenity::all::{ChannelId, Context, CreateMessage, Mentionable, UserId};
+use tracing::warn;
+
+use crate::{ControlPanel, TempVoiceRow};
+
+/// Used when a guild enables welcome messages without writing its own.
+const DEFAULT_WELCOME_MESSAGE: &str =
+    "Welcome to {channel}! This channel belongs to {owner}.\n\n{commands}";
+
+const COMMANDS: &str = "**Quick commands**
+`/voice name` Rename the channel
+`/voice limit` Set a user limit
+`/voice privacy` Lock or hide the channel
+`/voice trust` Let someone in
+`/voice kick` Remove someone
+`/voice claim` Take over once the owner leaves";
+
+/// Posts the guild's welcome message into a new channel's text chat.
+///
+/// `{owner}`, `{channel}` and `{commands}` in the template are replaced with
+/// the owner's mention, the channel's mention and a quick command list.
+pub(crate) async fn send_welcome(
+    ctx: &Context,
+    guild_data: &TempVoiceRow,
+    channel_id: ChannelId,
+    owner_id: UserId,
+) {
+    if !guild_data.welcome() {
+        return;
+    }
+
+    let content = guild_data
+        .welcome_message()
+        .unwrap_or(DEFAULT_WELCOME_MESSAGE)
+        .replace("{owner}", &owner_id.mention().to_string())
+        .replace("{channel}", &channel_id.mention().to_string())
+        .replace("{commands}", COMMANDS);
+
+    let mut message = CreateMessage::new().content(content);
+
+    if guild_data.welcome_panel() {
+        message = message.components(ControlPanel::components());
+    }
+
+    // The channel works fine without its welcome message.
+    if let Err(e) = channel_id.send_message(ctx, message).await {
+        warn!(error = %e, %channel_id, "failed to send welcome message");
+    }
+}